@@ -0,0 +1,13 @@
+use blockanalysis::detect_oracle_mode;
+use oracles::EcbCbcOracle;
+use prng::SeededRng;
+
+#[test]
+fn set2_c11_ecb_cbc_detection_oracle() {
+    // random key, padding bytes & mode on every call
+    let oracle = EcbCbcOracle::new(SeededRng::from_test_env());
+    for _ in 0..100 {
+        let detected = detect_oracle_mode(|input: &[u8]| oracle.encrypt(input), 16);
+        assert_eq!(Some(detected), oracle.last_mode());
+    }
+}
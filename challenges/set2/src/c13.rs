@@ -0,0 +1,24 @@
+use aes::{Aes128, Modes};
+use attacks::cut_and_paste::forge_tail;
+use kvcookie::{profile_for, Cookie};
+use prng::{Rng, SeededRng};
+
+#[test]
+fn set2_c13_ecb_cut_and_paste() {
+    let mut rng = SeededRng::from_test_env();
+    let key = rng.random_bytes(16);
+    let encrypt_profile = |email: &[u8]| {
+        let profile = profile_for(String::from_utf8(email.to_vec()).unwrap()).unwrap();
+        Aes128::new().encrypt(profile, &key).unwrap()
+    };
+    let decrypt_profile = |cipher_text: &[u8]| {
+        let profile = Aes128::new().decrypt(cipher_text, &key).unwrap();
+        Cookie::parse(String::from_utf8(profile).unwrap()).unwrap()
+    };
+
+    // the codec itself can not be injected into
+    assert!(profile_for("foo@bar.com&role=admin").is_err());
+
+    let forged = forge_tail(encrypt_profile, "user".len(), b"admin").unwrap();
+    assert_eq!(decrypt_profile(&forged).get("role"), Some("admin"));
+}
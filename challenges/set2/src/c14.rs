@@ -0,0 +1,19 @@
+use attacks::byte_at_a_time::recover_suffix;
+use oracles::EcbSuffixOracle;
+use prng::SeededRng;
+
+const UNKNOWN_STRING: &str = "Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkg\
+                              aGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBq\
+                              dXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUgYnkK";
+
+#[test]
+fn set2_c14_byte_at_a_time_ecb_decryption_harder() {
+    let unknown = naivebase64::decode(UNKNOWN_STRING).unwrap();
+    // random count of random bytes before the input
+    let oracle = EcbSuffixOracle::with_random_prefix(SeededRng::from_test_env(), unknown.clone());
+
+    assert_eq!(
+        recover_suffix(|input: &[u8]| oracle.encrypt(input)).unwrap(),
+        unknown
+    );
+}
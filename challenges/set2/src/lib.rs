@@ -2,4 +2,19 @@
 mod c9;
 
 #[cfg(test)]
-mod c10;
\ No newline at end of file
+mod c10;
+
+#[cfg(test)]
+mod c11;
+
+#[cfg(test)]
+mod c12;
+
+#[cfg(test)]
+mod c13;
+
+#[cfg(test)]
+mod c14;
+
+#[cfg(test)]
+mod c16;
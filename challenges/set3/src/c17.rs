@@ -0,0 +1,41 @@
+use aes::{
+    cipher::AesCipher, modes::CbcMode, padding::NoPadding, padding::PKCS7Padding, Aes128CBC, Modes,
+};
+use attacks::padding_oracle::PaddingOracle;
+use prng::{Rng, SeededRng};
+
+const STRINGS: [&str; 10] = [
+    "MDAwMDAwTm93IHRoYXQgdGhlIHBhcnR5IGlzIGp1bXBpbmc=",
+    "MDAwMDAxV2l0aCB0aGUgYmFzcyBraWNrZWQgaW4gYW5kIHRoZSBWZWdhJ3MgYXJlIHB1bXBpbic=",
+    "MDAwMDAyUXVpY2sgdG8gdGhlIHBvaW50LCB0byB0aGUgcG9pbnQsIG5vIGZha2luZw==",
+    "MDAwMDAzQ29va2luZyBNQydzIGxpa2UgYSBwb3VuZCBvZiBiYWNvbg==",
+    "MDAwMDA0QnVybmluZyAnZW0sIGlmIHlvdSBhaW4ndCBxdWljayBhbmQgbmltYmxl",
+    "MDAwMDA1SSBnbyBjcmF6eSB3aGVuIEkgaGVhciBhIGN5bWJhbA==",
+    "MDAwMDA2QW5kIGEgaGlnaCBoYXQgd2l0aCBhIHNvdXBlZCB1cCB0ZW1wbw==",
+    "MDAwMDA3SSdtIG9uIGEgcm9sbCwgaXQncyB0aW1lIHRvIGdvIHNvbG8=",
+    "MDAwMDA4b2xsaW4nIGluIG15IGZpdmUgcG9pbnQgb2g=",
+    "MDAwMDA5aXRoIG15IHJhZy10b3AgZG93biBzbyBteSBoYWlyIGNhbiBibG93",
+];
+
+#[test]
+fn set3_c17_cbc_padding_oracle() {
+    let mut rng = SeededRng::from_test_env();
+    let key = rng.random_bytes(16);
+    // decrypts without removing the padding, and only reports whether it is valid
+    let padding_oracle = |iv: &[u8], cipher_text: &[u8]| {
+        let plain_text = CbcMode::<AesCipher<128>, 16, NoPadding>::with_iv(iv.to_vec())
+            .decrypt(cipher_text, &key)
+            .unwrap();
+        PKCS7Padding::is_valid::<16>(&plain_text)
+    };
+    let attack = PaddingOracle::new(padding_oracle, 16);
+
+    for s in STRINGS {
+        let plain_text = naivebase64::decode(s).unwrap();
+        let iv = rng.random_bytes(16);
+        let cipher_text = Aes128CBC::with_iv(iv.clone())
+            .encrypt(&plain_text, &key)
+            .unwrap();
+        assert_eq!(attack.decrypt(&iv, &cipher_text).unwrap(), plain_text);
+    }
+}
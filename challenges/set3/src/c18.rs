@@ -0,0 +1,16 @@
+use aes::{Aes128Ctr, Modes};
+
+#[test]
+fn set3_c18_implement_ctr() {
+    let cipher_text = naivebase64::decode(
+        "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+    )
+    .unwrap();
+    let dec = Aes128Ctr::new()
+        .decrypt(cipher_text, "YELLOW SUBMARINE")
+        .unwrap();
+    assert_eq!(
+        "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ",
+        String::from_utf8(dec).unwrap()
+    );
+}
@@ -0,0 +1,42 @@
+use aes::{Aes128Ctr, Modes};
+use attacks::fixed_nonce_ctr::FixedNonceCtr;
+use prng::{Rng, SeededRng};
+
+#[test]
+fn set3_c19_break_fixed_nonce_ctr() {
+    let mut rng = SeededRng::from_test_env();
+    let key = rng.random_bytes(16);
+    let plain_texts = std::fs::read_to_string("src/c19_data.txt")
+        .unwrap()
+        .lines()
+        .map(|l| naivebase64::decode(l).unwrap())
+        .collect::<Vec<_>>();
+    let cipher_texts = plain_texts
+        .iter()
+        .map(|p| Aes128Ctr::new().encrypt(p, &key).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut attack = FixedNonceCtr::new(&cipher_texts);
+    let keystream = attack.solve_truncated().to_vec();
+    let expected = Aes128Ctr::new()
+        .encrypt(vec![0; keystream.len()], &key)
+        .unwrap();
+    let correct = keystream
+        .iter()
+        .zip(&expected)
+        .filter(|(a, b)| a == b)
+        .count();
+    assert!(
+        correct * 4 >= keystream.len() * 3,
+        "{correct} / {}",
+        keystream.len()
+    );
+
+    // the end of the longest line is guessed from a single sample, an analyst
+    // recognising it pins it and every line decrypts in full
+    attack.solve();
+    attack
+        .pin(37, 0, b"He, too, has been changed in his turn,")
+        .unwrap();
+    assert_eq!(attack.plain_texts(), plain_texts);
+}
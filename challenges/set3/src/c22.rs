@@ -0,0 +1,28 @@
+use attacks::mt_seed::recover_timestamp_seed;
+use prng::{Clock, Mt19937, Rng, SeededRng, VirtualClock};
+
+// seconds in [40, 1000]
+fn random_wait<R: Rng>(rng: &mut R) -> u64 {
+    40 + rng.gen_range(0..961)
+}
+
+// seeds from the current time, between two waits
+fn routine<C: Clock, R: Rng>(clock: &VirtualClock, service: C, waits: &mut R) -> u32 {
+    clock.advance(random_wait(waits));
+    let mut rng = Mt19937::new(service.now() as u32);
+    clock.advance(random_wait(waits));
+    rng.next_u32()
+}
+
+#[test]
+fn set3_c22_crack_mt19937_seed() {
+    // time passes instantly
+    let clock = VirtualClock::new(1_760_000_000);
+    let start = clock.now();
+    let output = routine(&clock, clock.clone(), &mut SeededRng::from_test_env());
+
+    let now = clock.now();
+    let seed = recover_timestamp_seed(output, now - 2000..=now, 4).unwrap();
+    assert!(seed as u64 > start && (seed as u64) < now);
+    assert_eq!(Mt19937::new(seed).next_u32(), output);
+}
@@ -0,0 +1,20 @@
+use attacks::mt_clone::clone_mt19937;
+use prng::{Mt19937, Rng, SeededRng};
+
+#[test]
+fn set3_c23_clone_mt19937() {
+    let mut rng = SeededRng::from_test_env();
+    let seed = rng.next_u32();
+    let mut mt = Mt19937::new(seed);
+    // tap the generator somewhere in the middle of a twist
+    (0..rng.gen_range(0..256)).for_each(|_| {
+        mt.next_u32();
+    });
+
+    let outputs = (0..700).map(|_| mt.next_u32()).collect::<Vec<_>>();
+    let cloned = clone_mt19937(&outputs).unwrap();
+    assert!(cloned.is_consistent());
+
+    let mut clone = cloned.rng;
+    assert!((0..10_000).all(|_| clone.next_u32() == mt.next_u32()));
+}
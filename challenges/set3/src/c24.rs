@@ -0,0 +1,35 @@
+use attacks::mt_stream::{detect_time_seeded_token, recover_stream_key};
+use prng::{Clock, Mt19937, Mt19937Cipher, Rng, SeededRng, VirtualClock};
+
+#[test]
+fn set3_c24_mt19937_stream_cipher() {
+    let mut rng = SeededRng::from_test_env();
+    let cipher = Mt19937Cipher::new();
+    let key = rng.random_bytes(2);
+
+    // random count of random characters followed by 14 known 'A's
+    let prefix_len = 5 + rng.gen_range(0..20) as usize;
+    let prefix = rng.random_bytes(prefix_len);
+    let plain_text = [prefix, vec![b'A'; 14]].concat();
+    let cipher_text = cipher.encrypt(&plain_text, &key).unwrap();
+    assert_eq!(cipher.decrypt(&cipher_text, &key).unwrap(), plain_text);
+
+    let recovered = recover_stream_key(&cipher_text, &[b'A'; 14], 4).unwrap();
+    assert_eq!(recovered.to_le_bytes(), key[..]);
+}
+
+#[test]
+fn set3_c24_password_reset_token() {
+    let clock = VirtualClock::new(1_760_000_000);
+    let reset_token = || Mt19937::new(clock.now() as u32).random_bytes(16);
+
+    let token = reset_token();
+    clock.advance(42);
+    assert!(detect_time_seeded_token(&token, &clock, 3600, 4).is_some());
+
+    let not_time_seeded = SeededRng::from_test_env().random_bytes(16);
+    assert_eq!(
+        detect_time_seeded_token(&not_time_seeded, &clock, 3600, 4),
+        None
+    );
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod c17;
+
+#[cfg(test)]
+mod c18;
+
+#[cfg(test)]
+mod c19;
+
+#[cfg(test)]
+mod c20;
+
+#[cfg(test)]
+mod c21;
+
+#[cfg(test)]
+mod c22;
+
+#[cfg(test)]
+mod c23;
+
+#[cfg(test)]
+mod c24;
@@ -0,0 +1,28 @@
+use aes::{Aes128, Aes128Ctr, Modes};
+use attacks::ctr_edit::recover_with_edit;
+use prng::{Rng, SeededRng};
+
+#[test]
+fn set4_c25_break_random_access_read_write_aes_ctr() {
+    let data = naivebase64::decode(
+        std::fs::read_to_string("src/c25_data.txt")
+            .unwrap()
+            .replace('\n', ""),
+    )
+    .unwrap();
+    let plain_text = Aes128::new().decrypt(data, "YELLOW SUBMARINE").unwrap();
+
+    let mut rng = SeededRng::from_test_env();
+    let key = rng.random_bytes(16);
+    let ctr = Aes128Ctr::with_nonce(rng.random_bytes(8));
+    let cipher_text = ctr.encrypt(&plain_text, &key).unwrap();
+    // exposed to the attacker, the key stays hidden
+    let edit =
+        |c: &[u8], offset: usize, new_text: &[u8]| ctr.edit(c, &key, offset, new_text).unwrap();
+
+    let recovered = recover_with_edit(&cipher_text, edit).unwrap();
+    assert_eq!(recovered, plain_text);
+    assert!(String::from_utf8(recovered)
+        .unwrap()
+        .starts_with("I'm back and I'm ringin' the bell"));
+}
@@ -0,0 +1,29 @@
+use aes::{Aes128Ctr, Modes};
+use attacks::bitflip::inject;
+use prng::{Rng, SeededRng};
+
+const PREFIX: &[u8] = b"comment1=cooking%20MCs;userdata=";
+const SUFFIX: &[u8] = b";comment2=%20like%20a%20pound%20of%20bacon";
+
+#[test]
+fn set4_c26_ctr_bitflipping() {
+    let mut rng = SeededRng::from_test_env();
+    let key = rng.random_bytes(16);
+    let ctr = Aes128Ctr::with_nonce(rng.random_bytes(8));
+    let encrypt = |input: &[u8]| {
+        let quoted = String::from_utf8_lossy(input)
+            .replace(';', "%3B")
+            .replace('=', "%3D");
+        ctr.encrypt([PREFIX, quoted.as_bytes(), SUFFIX].concat(), &key)
+            .unwrap()
+    };
+    let is_admin = |cipher_text: &[u8]| {
+        let plain_text = ctr.decrypt(cipher_text, &key).unwrap();
+        String::from_utf8_lossy(&plain_text)
+            .split(';')
+            .any(|pair| pair == "admin=true")
+    };
+
+    assert!(!is_admin(&encrypt(b";admin=true;")));
+    assert!(is_admin(&inject(encrypt, b";admin=true;").unwrap()));
+}
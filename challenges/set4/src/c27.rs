@@ -0,0 +1,33 @@
+use aes::{Aes128CBC, Modes};
+use attacks::key_as_iv::recover_key;
+use prng::{Rng, SeededRng};
+
+#[test]
+fn set4_c27_recover_key_from_cbc_with_iv_key() {
+    let mut rng = SeededRng::from_test_env();
+    let key = rng.random_bytes(16);
+    let cbc = Aes128CBC::with_key_as_iv();
+    let cipher_text = cbc
+        .encrypt(
+            "comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon",
+            &key,
+        )
+        .unwrap();
+    // the receiver complains about high-ASCII plaintext, echoing it back
+    let receiver = |c: &[u8]| {
+        let plain_text = cbc.decrypt(c, &key).unwrap();
+        match plain_text.is_ascii() {
+            true => Ok(()),
+            false => Err(plain_text),
+        }
+    };
+
+    let recovered = recover_key(&cipher_text, 16, receiver).unwrap();
+    assert_eq!(recovered, key);
+    assert_eq!(
+        Aes128CBC::with_iv(recovered.clone())
+            .decrypt(&cipher_text, &recovered)
+            .unwrap(),
+        cbc.decrypt(&cipher_text, &key).unwrap()
+    );
+}
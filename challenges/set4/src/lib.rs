@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod c25;
+
+#[cfg(test)]
+mod c26;
+
+#[cfg(test)]
+mod c27;
@@ -0,0 +1,37 @@
+use std::thread;
+
+use attacks::rc4_bias::{recover_secret, Bias};
+use prng::{Rng, SeededRng};
+use rc4::Rc4;
+
+const COOKIE: &str = "QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F";
+
+#[test]
+#[ignore = "2^29 RC4 encryptions, run with --release"]
+fn set7_c56_rc4_single_byte_biases() {
+    let cookie = naivebase64::decode(COOKIE).unwrap();
+    // request || cookie under a fresh key every time
+    let oracle = |request: &[u8], rng: &mut SeededRng| {
+        let key = rng.random_bytes(16);
+        Rc4::new()
+            .encrypt([request, &cookie].concat(), key)
+            .unwrap()
+    };
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let recovered = recover_secret(
+        oracle,
+        cookie.len(),
+        &[Bias::Z16, Bias::Z32],
+        1 << 24,
+        threads,
+        SeededRng::from_test_env().seed(),
+        |p| {
+            if p.samples % (1 << 24) == 0 {
+                eprintln!("{}/{} samples", p.samples, p.total);
+            }
+        },
+    )
+    .unwrap();
+    assert_eq!(recovered, b"BE SURE TO DRINK YOUR OVALTINE");
+}
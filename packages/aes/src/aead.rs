@@ -0,0 +1,277 @@
+use std::marker::PhantomData;
+
+use crate::{cipher::BlockCipher, mac::Mac, padding::CipherPadding, Modes};
+
+/// Encrypt-then-MAC composition of any block mode `M` with a MAC `A`.
+///
+/// The key is `MAC_KEY || ENC_KEY` split in two equal halves (as in RFC 7518
+/// AES_CBC_HMAC), the output is `C || T` where
+/// `T = MAC(AAD || IV || C || bitlen(AAD) as u64 BE)`.
+/// The tag is checked in constant time before the ciphertext is decrypted or
+/// unpadded, so a tampered ciphertext never reaches the padding check.
+#[derive(Debug)]
+pub struct EncryptThenMac<M, A, C, const B: usize, D>
+where
+    M: Modes<C, B, D>,
+    A: Mac,
+    C: BlockCipher<B, Unit = u8>,
+    D: CipherPadding,
+{
+    mode: M,
+    _marker_a: PhantomData<A>,
+    _marker_c: PhantomData<C>,
+    _marker_d: PhantomData<D>,
+}
+
+impl<M, A, C, const B: usize, D> EncryptThenMac<M, A, C, B, D>
+where
+    M: Modes<C, B, D>,
+    A: Mac,
+    C: BlockCipher<B, Unit = u8>,
+    D: CipherPadding,
+{
+    pub fn new(mode: M) -> Self {
+        EncryptThenMac {
+            mode,
+            _marker_a: PhantomData,
+            _marker_c: PhantomData,
+            _marker_d: PhantomData,
+        }
+    }
+
+    pub fn mode(&self) -> &M {
+        &self.mode
+    }
+
+    pub fn encrypt_with_aad<P: AsRef<[u8]>, T: AsRef<[u8]>, U: AsRef<[u8]>>(
+        &self,
+        plain_text: P,
+        key: T,
+        aad: U,
+    ) -> anyhow::Result<Vec<u8>> {
+        let (mac_key, enc_key) = split_key(key.as_ref())?;
+        let mut sealed = self.mode.encrypt(plain_text, enc_key)?;
+        let tag = self.tag(mac_key, aad.as_ref(), &sealed);
+        sealed.extend(tag);
+        Ok(sealed)
+    }
+
+    pub fn decrypt_with_aad<V: AsRef<[u8]>, T: AsRef<[u8]>, U: AsRef<[u8]>>(
+        &self,
+        sealed: V,
+        key: T,
+        aad: U,
+    ) -> anyhow::Result<Vec<u8>> {
+        let (mac_key, enc_key) = split_key(key.as_ref())?;
+        let sealed = sealed.as_ref();
+        if sealed.len() < A::TAG_LEN {
+            anyhow::bail!("EncryptThenMac: input shorter than tag");
+        }
+
+        let (cipher_text, tag) = sealed.split_at(sealed.len() - A::TAG_LEN);
+        let mut mac = A::new_mac(mac_key);
+        self.feed(&mut mac, aad.as_ref(), cipher_text);
+        if !mac.verify(tag) {
+            anyhow::bail!("EncryptThenMac: authentication failed");
+        }
+
+        self.mode.decrypt(cipher_text, enc_key)
+    }
+
+    fn tag(&self, mac_key: &[u8], aad: &[u8], cipher_text: &[u8]) -> Vec<u8> {
+        let mut mac = A::new_mac(mac_key);
+        self.feed(&mut mac, aad, cipher_text);
+        mac.finalize()
+    }
+
+    fn feed(&self, mac: &mut A, aad: &[u8], cipher_text: &[u8]) {
+        mac.update(aad);
+        mac.update(self.mode.iv());
+        mac.update(cipher_text);
+        mac.update((aad.len() as u64 * 8).to_be_bytes());
+    }
+}
+
+impl<M, A, C, const B: usize, D> Modes<C, B, D> for EncryptThenMac<M, A, C, B, D>
+where
+    M: Modes<C, B, D>,
+    A: Mac,
+    C: BlockCipher<B, Unit = u8>,
+    D: CipherPadding,
+{
+    fn encrypt<P: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        plain_text: P,
+        key: T,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.encrypt_with_aad(plain_text, key, [])
+    }
+
+    fn decrypt<U: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        cipher_text: U,
+        key: T,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.decrypt_with_aad(cipher_text, key, [])
+    }
+
+    fn iv(&self) -> &[u8] {
+        self.mode.iv()
+    }
+}
+
+fn split_key(key: &[u8]) -> anyhow::Result<(&[u8], &[u8])> {
+    if key.is_empty() || !key.len().is_multiple_of(2) {
+        anyhow::bail!(
+            "EncryptThenMac: key ({} bytes) should be MAC_KEY || ENC_KEY of equal length",
+            key.len()
+        );
+    }
+    Ok(key.split_at(key.len() / 2))
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::EncryptThenMac;
+    use crate::{
+        cipher::AesCipher, mac::Mac, padding::PKCS7Padding, Aes128, Aes128CBC, Aes128CbcCmac,
+        Aes128Cmac, Modes,
+    };
+
+    // length prefixed CBC-MAC, only to exercise the composition
+    struct TestMac {
+        key: Vec<u8>,
+        data: Vec<u8>,
+    }
+
+    impl Mac for TestMac {
+        const TAG_LEN: usize = 16;
+
+        fn new_mac<K: AsRef<[u8]>>(key: K) -> Self {
+            TestMac {
+                key: key.as_ref().to_vec(),
+                data: vec![],
+            }
+        }
+
+        fn update<T: AsRef<[u8]>>(&mut self, data: T) {
+            self.data.extend_from_slice(data.as_ref());
+        }
+
+        fn finalize(self) -> Vec<u8> {
+            let msg = [
+                &(self.data.len() as u64).to_be_bytes(),
+                self.data.as_slice(),
+            ]
+            .concat();
+            let enc = Aes128CBC::new().encrypt(msg, &self.key).unwrap();
+            enc[enc.len() - 16..].to_vec()
+        }
+    }
+
+    // counts how often the wrapped mode is asked to decrypt
+    struct CountingMode {
+        inner: Aes128CBC,
+        decrypts: Cell<usize>,
+    }
+
+    impl Modes<AesCipher<128>, 16, PKCS7Padding> for CountingMode {
+        fn encrypt<P: AsRef<[u8]>, T: AsRef<[u8]>>(
+            &self,
+            plain_text: P,
+            key: T,
+        ) -> anyhow::Result<Vec<u8>> {
+            self.inner.encrypt(plain_text, key)
+        }
+
+        fn decrypt<U: AsRef<[u8]>, T: AsRef<[u8]>>(
+            &self,
+            cipher_text: U,
+            key: T,
+        ) -> anyhow::Result<Vec<u8>> {
+            self.decrypts.set(self.decrypts.get() + 1);
+            self.inner.decrypt(cipher_text, key)
+        }
+
+        fn iv(&self) -> &[u8] {
+            self.inner.iv()
+        }
+    }
+
+    const KEY: &[u8; 32] = b"MAC KEY 16 BYTESYELLOW SUBMARINE";
+
+    #[test]
+    fn test_etm_roundtrip() {
+        let etm = EncryptThenMac::<_, TestMac, _, 16, _>::new(Aes128CBC::with_iv(vec![7; 16]));
+        let sealed = etm
+            .encrypt_with_aad("attack at dawn", KEY, "header")
+            .unwrap();
+        assert_eq!(sealed.len(), 16 + 16);
+        assert_eq!(
+            etm.decrypt_with_aad(&sealed, KEY, "header").unwrap(),
+            b"attack at dawn"
+        );
+
+        let etm_ecb = EncryptThenMac::<_, TestMac, _, 16, _>::new(Aes128::new());
+        let sealed = etm_ecb.encrypt("no aad", KEY).unwrap();
+        assert_eq!(etm_ecb.decrypt(sealed, KEY).unwrap(), b"no aad");
+    }
+
+    #[test]
+    fn test_etm_rejects_before_decrypt() {
+        let etm = EncryptThenMac::<_, TestMac, _, 16, _>::new(CountingMode {
+            inner: Aes128CBC::with_iv(vec![1; 16]),
+            decrypts: Cell::new(0),
+        });
+        let sealed = etm
+            .encrypt_with_aad("legacy cbc pipeline", KEY, "v1")
+            .unwrap();
+
+        // flip a bit in every position, including the tag
+        for i in 0..sealed.len() {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 1;
+            assert!(etm.decrypt_with_aad(&tampered, KEY, "v1").is_err());
+        }
+        assert!(etm.decrypt_with_aad(&sealed, KEY, "v2").is_err());
+        assert!(etm.decrypt_with_aad(&sealed[..8], KEY, "v1").is_err());
+        assert_eq!(etm.mode().decrypts.get(), 0);
+
+        assert!(etm.decrypt_with_aad(&sealed, KEY, "v1").is_ok());
+        assert_eq!(etm.mode().decrypts.get(), 1);
+    }
+
+    #[test]
+    fn test_etm_authenticates_iv() {
+        let sealed = EncryptThenMac::<_, TestMac, _, 16, _>::new(Aes128CBC::with_iv(vec![1; 16]))
+            .encrypt("iv is bound", KEY)
+            .unwrap();
+        let other_iv = EncryptThenMac::<_, TestMac, _, 16, _>::new(Aes128CBC::with_iv(vec![2; 16]));
+        assert!(other_iv.decrypt(sealed, KEY).is_err());
+    }
+
+    #[test]
+    fn test_etm_cmac() {
+        let etm = Aes128CbcCmac::new(Aes128CBC::with_iv(vec![3; 16]));
+        let sealed = etm.encrypt_with_aad("with cmac", KEY, "aad").unwrap();
+        assert_eq!(
+            &sealed[sealed.len() - 16..],
+            Aes128Cmac::mac(
+                &KEY[..16],
+                [b"aad", &[3; 16][..], &sealed[..16], &24u64.to_be_bytes()].concat()
+            )
+        );
+        assert_eq!(
+            etm.decrypt_with_aad(sealed, KEY, "aad").unwrap(),
+            b"with cmac"
+        );
+    }
+
+    #[test]
+    fn test_etm_key_split() {
+        let etm = EncryptThenMac::<_, TestMac, _, 16, _>::new(Aes128::new());
+        assert!(etm.encrypt("odd key", &KEY[..31]).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::secret::Secret;
 use crate::tables::{
     GMUL_11, GMUL_13, GMUL_14, GMUL_2, GMUL_3, GMUL_9, INV_SBOX_TABLE, RCON_RC, SBOX_TABLE,
 };
@@ -6,23 +7,33 @@ use crate::utils::word4;
 pub trait BlockCipher<const B: usize> {
     type Unit;
 
-    fn cipher<T: AsRef<[u8]>>(block: [Self::Unit; B], key: T) -> [Self::Unit; B];
-    fn inv_cipher<T: AsRef<[u8]>>(block: [Self::Unit; B], key: T) -> [Self::Unit; B];
+    /// Encrypt `block` in place, a block held in a [`Secret`] is never
+    /// copied out of it.
+    fn cipher_in_place<T: AsRef<[u8]>>(block: &mut [Self::Unit; B], key: T);
+    fn inv_cipher_in_place<T: AsRef<[u8]>>(block: &mut [Self::Unit; B], key: T);
+
+    fn cipher<T: AsRef<[u8]>>(mut block: [Self::Unit; B], key: T) -> [Self::Unit; B] {
+        Self::cipher_in_place(&mut block, key);
+        block
+    }
+
+    fn inv_cipher<T: AsRef<[u8]>>(mut block: [Self::Unit; B], key: T) -> [Self::Unit; B] {
+        Self::inv_cipher_in_place(&mut block, key);
+        block
+    }
 }
 
 impl<const NK: usize> BlockCipher<16> for AesCipher<NK> {
     type Unit = u8;
 
-    fn cipher<T: AsRef<[u8]>>(mut block: [Self::Unit; 16], key: T) -> [Self::Unit; 16] {
+    fn cipher_in_place<T: AsRef<[u8]>>(block: &mut [Self::Unit; 16], key: T) {
         let key_sch = Self::key_expansion(key);
-        Self::block_cipher(&mut block, key_sch);
-        block
+        Self::block_cipher(block, &key_sch);
     }
 
-    fn inv_cipher<T: AsRef<[u8]>>(mut block: [Self::Unit; 16], key: T) -> [Self::Unit; 16] {
+    fn inv_cipher_in_place<T: AsRef<[u8]>>(block: &mut [Self::Unit; 16], key: T) {
         let key_sch = Self::key_expansion(key);
-        Self::block_inv_cipher(&mut block, key_sch);
-        block
+        Self::block_inv_cipher(block, &key_sch);
     }
 }
 
@@ -37,7 +48,7 @@ impl<const K: usize> AesCipher<K> {
     };
     const NK: usize = K / (4 * 8);
 
-    pub(crate) fn key_expansion<T: AsRef<[u8]>>(key: T) -> Vec<u8> {
+    pub(crate) fn key_expansion<T: AsRef<[u8]>>(key: T) -> Secret<Vec<u8>> {
         let key = key.as_ref();
         assert_eq!(
             key.len() / 4,
@@ -53,7 +64,8 @@ impl<const K: usize> AesCipher<K> {
         //     E / 4,
         //     16 * (NR + 1)
         // );
-        let mut expanded = vec![0; 16 * (Self::NR + 1)];
+        let mut expanded = Secret::new(vec![0; 16 * (Self::NR + 1)]);
+        let mut temp = Secret::new([0u8; 4]);
 
         // First NK words are equal to key.
         for i in 0..key.len() {
@@ -62,7 +74,7 @@ impl<const K: usize> AesCipher<K> {
 
         // iter over WORDS (4 bytes)
         for i in Self::NK..(4 * (Self::NR + 1)) {
-            let mut temp = [
+            *temp = [
                 expanded[(i - 1) * 4 + 0],
                 expanded[(i - 1) * 4 + 1],
                 expanded[(i - 1) * 4 + 2],
@@ -91,13 +103,13 @@ impl<const K: usize> AesCipher<K> {
         expanded
     }
 
-    pub(crate) fn block_cipher(block: &mut [u8; 16], round_keys: Vec<u8>) {
+    pub(crate) fn block_cipher(block: &mut [u8; 16], round_keys: &[u8]) {
         // println!("round[0].input = {}", hex::encode(&block).unwrap());
         // println!(
         //     "round[0].k_sch = {}",
         //     hex::encode(word4(round_keys, 0).unwrap()).unwrap()
         // );
-        Self::add_round_key(block, word4(&round_keys, 0).unwrap());
+        Self::add_round_key(block, word4(round_keys, 0).unwrap());
 
         for r in 1..Self::NR {
             // println!("round[{r}].start = {}", hex::encode(&block).unwrap());
@@ -107,7 +119,7 @@ impl<const K: usize> AesCipher<K> {
             // println!("round[{r}].s_row = {}", hex::encode(&block).unwrap());
             Self::mix_columns(block);
             // println!("round[{r}].m_col = {}", hex::encode(&block).unwrap());
-            Self::add_round_key(block, word4(&round_keys, r * 4).unwrap());
+            Self::add_round_key(block, word4(round_keys, r * 4).unwrap());
             // println!(
             //     "round[{r}].k_sch = {}",
             //     hex::encode(word4(round_keys, r * 4).unwrap()).unwrap()
@@ -119,7 +131,7 @@ impl<const K: usize> AesCipher<K> {
         // println!("round[{NR}].s_box = {}", hex::encode(&block).unwrap());
         Self::shift_rows(block);
         // println!("round[{NR}].s_row = {}", hex::encode(&block).unwrap());
-        Self::add_round_key(block, word4(&round_keys, Self::NR * 4).unwrap());
+        Self::add_round_key(block, word4(round_keys, Self::NR * 4).unwrap());
         // println!(
         //     "round[{NR}].k_sch = {}",
         //     hex::encode(word4(round_keys, NR * 4).unwrap()).unwrap()
@@ -127,14 +139,14 @@ impl<const K: usize> AesCipher<K> {
         // println!("round[{NR}].output = {}", hex::encode(&block).unwrap());
     }
 
-    pub(crate) fn block_inv_cipher(block: &mut [u8; 16], round_keys: Vec<u8>) {
+    pub(crate) fn block_inv_cipher(block: &mut [u8; 16], round_keys: &[u8]) {
         // println!("round[0].iinput = {}", hex::encode(&block).unwrap());
         // println!(
         //     "round[0].ik_sch = {}",
         //     // hex::encode(round_keys).unwrap()
         //     hex::encode(word4(round_keys, NR * 4).unwrap()).unwrap()
         // );
-        Self::add_round_key(block, word4(&round_keys, Self::NR * 4).unwrap());
+        Self::add_round_key(block, word4(round_keys, Self::NR * 4).unwrap());
 
         for r in (1..Self::NR).rev() {
             // println!("round[{r}].istart = {}", hex::encode(&block).unwrap());
@@ -142,7 +154,7 @@ impl<const K: usize> AesCipher<K> {
             // println!("round[{r}].is_row = {}", hex::encode(&block).unwrap());
             Self::inv_sub_bytes(block);
             // println!("round[{r}].is_box = {}", hex::encode(&block).unwrap());
-            Self::add_round_key(block, word4(&round_keys, r * 4).unwrap());
+            Self::add_round_key(block, word4(round_keys, r * 4).unwrap());
             // println!(
             //     "round[{r}].ik_sch = {}",
             //     hex::encode(word4(round_keys, r * 4).unwrap()).unwrap()
@@ -156,7 +168,7 @@ impl<const K: usize> AesCipher<K> {
         // println!("round[{NR}].is_row = {}", hex::encode(&block).unwrap());
         Self::inv_sub_bytes(block);
         // println!("round[{NR}].is_box = {}", hex::encode(&block).unwrap());
-        Self::add_round_key(block, word4(&round_keys, 0).unwrap());
+        Self::add_round_key(block, word4(round_keys, 0).unwrap());
         // println!(
         //     "round[{NR}].ik_sch = {}",
         //     hex::encode(word4(round_keys, 0).unwrap()).unwrap()
@@ -182,7 +194,7 @@ impl<const K: usize> AesCipher<K> {
 
     pub(crate) fn shift_rows(block: &mut [u8; 16]) {
         // TODO: Implement inplace instead of copying block
-        let copy = block.clone();
+        let copy = Secret::new(*block);
 
         // // 1st row is not changed
         // block[0 + 0 * 4] = copy[0 + 0 * 4];
@@ -213,7 +225,7 @@ impl<const K: usize> AesCipher<K> {
 
     pub(crate) fn inv_shift_rows(block: &mut [u8; 16]) {
         // TODO: Implement inplace instead of copying block
-        let copy = block.clone();
+        let copy = Secret::new(*block);
 
         block[1 + 1 * 4] = copy[1 + 0 * 4];
         block[2 + 2 * 4] = copy[2 + 0 * 4];
@@ -238,7 +250,7 @@ impl<const K: usize> AesCipher<K> {
 
     pub(crate) fn mix_columns(block: &mut [u8; 16]) {
         // TODO: Implement inplace instead of copying block
-        let copy = block.clone();
+        let copy = Secret::new(*block);
 
         for c in 0..4 {
             block[0 + c * 4] = GMUL_2[copy[0 + c * 4] as usize]
@@ -265,7 +277,7 @@ impl<const K: usize> AesCipher<K> {
 
     pub(crate) fn inv_mix_columns(block: &mut [u8; 16]) {
         // TODO: Implement inplace instead of copying block
-        let copy = block.clone();
+        let copy = Secret::new(*block);
 
         for c in 0..4 {
             block[0 + c * 4] = GMUL_14[copy[0 + c * 4] as usize]
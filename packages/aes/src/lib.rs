@@ -1,13 +1,18 @@
+use aead::EncryptThenMac;
 use cipher::AesCipher;
-use modes::{CbcMode, EcbMode};
+use mac::{CbcMac, Cmac};
+use modes::{CbcMode, ChainedCbcMode, CtrMode, EcbMode};
 use padding::PKCS7Padding;
 
 pub mod sbox;
 pub mod tables;
 
+pub mod aead;
 pub mod cipher;
+pub mod mac;
 pub mod modes;
 pub mod padding;
+pub mod secret;
 pub mod utils;
 
 pub use modes::Modes;
@@ -26,11 +31,34 @@ pub type Aes192CBC = CbcMode<AesCipher<192>, 16, PKCS7Padding>;
 // Aes256, CBC, PKCS7
 pub type Aes256CBC = CbcMode<AesCipher<256>, 16, PKCS7Padding>;
 
+// Aes128, CBC with IV chained across records, PKCS7
+pub type Aes128ChainedCBC = ChainedCbcMode<AesCipher<128>, 16, PKCS7Padding>;
+
+// Aes128, CTR
+pub type Aes128Ctr = CtrMode<AesCipher<128>, 16>;
+// Aes192, CTR
+pub type Aes192Ctr = CtrMode<AesCipher<192>, 16>;
+// Aes256, CTR
+pub type Aes256Ctr = CtrMode<AesCipher<256>, 16>;
+
+// Aes128, CMAC
+pub type Aes128Cmac = Cmac<AesCipher<128>, 16>;
+// Aes192, CMAC
+pub type Aes192Cmac = Cmac<AesCipher<192>, 16>;
+// Aes256, CMAC
+pub type Aes256Cmac = Cmac<AesCipher<256>, 16>;
+
+// Aes128, CBC-MAC, PKCS7
+pub type Aes128CbcMac = CbcMac<AesCipher<128>, 16, PKCS7Padding>;
+
+// Aes128, CBC, PKCS7, Encrypt-then-MAC with Aes128 CMAC
+pub type Aes128CbcCmac = EncryptThenMac<Aes128CBC, Aes128Cmac, AesCipher<128>, 16, PKCS7Padding>;
+
 #[cfg(test)]
 mod tests_helpers;
 #[cfg(test)]
 mod test {
-    use crate::{Aes128, Aes128CBC, Modes};
+    use crate::{Aes128, Aes128CBC, Aes128ChainedCBC, Aes128Ctr, Aes256CBC, Modes};
 
     use super::tests_helpers::Directive;
     use datadriven::{walk, Result as DDResult};
@@ -62,4 +90,102 @@ mod test {
         assert_eq!(text.data(), dec);
         assert_eq!(text.data(), dec_ecb);
     }
+
+    #[test]
+    fn aes_ctr_test() {
+        // cryptopals challenge 18
+        let cipher_text = naivebase64::decode(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+        )
+        .unwrap();
+        let key = "YELLOW SUBMARINE";
+        let dec = Aes128Ctr::new().decrypt(&cipher_text, key).unwrap();
+        assert_eq!(
+            String::from_utf8(dec.clone()).unwrap(),
+            "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+        );
+        assert_eq!(Aes128Ctr::new().encrypt(dec, key).unwrap(), cipher_text);
+
+        // any length, the last partial block uses part of the keystream
+        let ctr = Aes128Ctr::with_nonce(vec![7; 8]);
+        let enc = ctr.encrypt("attack at dawn", key).unwrap();
+        assert_eq!(enc.len(), 14);
+        assert_eq!(ctr.decrypt(enc, key).unwrap(), b"attack at dawn");
+        assert!(Aes128Ctr::with_nonce(vec![0; 16])
+            .encrypt("x", key)
+            .is_err());
+    }
+
+    #[test]
+    fn aes_ctr_seek_test() {
+        let key = "YELLOW SUBMARINE";
+        let ctr = Aes128Ctr::with_nonce(vec![1; 8]);
+        let text = b"random access is what encrypted log files need, any offset".to_vec();
+        let enc = ctr.encrypt(&text, key).unwrap();
+
+        for offset in [0, 1, 15, 16, 17, 33, text.len()] {
+            let tail = ctr
+                .apply_keystream_at(&text[offset..], key, offset)
+                .unwrap();
+            assert_eq!(tail, &enc[offset..]);
+            let dec = ctr.apply_keystream_at(&enc[offset..], key, offset).unwrap();
+            assert_eq!(dec, &text[offset..]);
+        }
+
+        let edited = ctr.edit(&enc, key, 7, "ACCESS").unwrap();
+        let mut expected = text.clone();
+        expected[7..13].copy_from_slice(b"ACCESS");
+        assert_eq!(ctr.decrypt(&edited, key).unwrap(), expected);
+        assert_eq!(edited[13..], enc[13..]);
+
+        // appending at the end grows the stream
+        let appended = ctr.edit(&enc, key, text.len(), "!").unwrap();
+        assert_eq!(
+            ctr.decrypt(appended, key).unwrap(),
+            [&text[..], b"!"].concat()
+        );
+        assert!(ctr.edit(&enc, key, text.len() + 1, "!").is_err());
+    }
+
+    #[test]
+    fn aes_cbc_key_as_iv_test() {
+        let key = "YELLOW SUBMARINE";
+        let enc = Aes128CBC::with_key_as_iv()
+            .encrypt("key as iv", key)
+            .unwrap();
+        assert_eq!(
+            enc,
+            Aes128CBC::with_iv(key.as_bytes().to_vec())
+                .encrypt("key as iv", key)
+                .unwrap()
+        );
+        assert_eq!(
+            Aes128CBC::with_key_as_iv().decrypt(enc, key).unwrap(),
+            b"key as iv"
+        );
+        // aes256 key is two blocks, can not be the iv
+        assert!(Aes256CBC::with_key_as_iv()
+            .encrypt("key as iv", [0; 32])
+            .is_err());
+    }
+
+    #[test]
+    fn aes_chained_cbc_test() {
+        let key = "YELLOW SUBMARINE";
+        let sender = Aes128ChainedCBC::with_iv(vec![1; 16]);
+        let receiver = Aes128ChainedCBC::with_iv(vec![1; 16]);
+
+        let first = sender.encrypt("first record", key).unwrap();
+        assert_eq!(sender.next_iv(), first[first.len() - 16..]);
+        let second = sender.encrypt("second record", key).unwrap();
+        assert_eq!(
+            second,
+            Aes128CBC::with_iv(first[first.len() - 16..].to_vec())
+                .encrypt("second record", key)
+                .unwrap()
+        );
+
+        assert_eq!(receiver.decrypt(first, key).unwrap(), b"first record");
+        assert_eq!(receiver.decrypt(second, key).unwrap(), b"second record");
+    }
 }
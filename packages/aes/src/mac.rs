@@ -0,0 +1,264 @@
+use std::{hint::black_box, marker::PhantomData};
+
+use crate::{cipher::BlockCipher, padding::CipherPadding, secret::Secret};
+
+/// Message authentication code with incremental input.
+pub trait Mac: Sized {
+    // length of the produced tag in bytes
+    const TAG_LEN: usize;
+
+    fn new_mac<K: AsRef<[u8]>>(key: K) -> Self;
+    fn update<T: AsRef<[u8]>>(&mut self, data: T);
+    fn finalize(self) -> Vec<u8>;
+
+    // constant time comparison of the computed tag with `tag`
+    fn verify<T: AsRef<[u8]>>(self, tag: T) -> bool {
+        ct_eq(self.finalize(), tag)
+    }
+
+    // one shot mac of `data`
+    fn mac<K: AsRef<[u8]>, T: AsRef<[u8]>>(key: K, data: T) -> Vec<u8> {
+        let mut mac = Self::new_mac(key);
+        mac.update(data);
+        mac.finalize()
+    }
+}
+
+/// Compare two byte strings in time independent of their content.
+///
+/// Only the lengths (which are public for tags) may short circuit.
+pub fn ct_eq<T: AsRef<[u8]>, U: AsRef<[u8]>>(a: T, b: U) -> bool {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a
+        .iter()
+        .zip(b)
+        .fold(0u8, |acc, (x, y)| black_box(acc | (x ^ y)));
+    black_box(diff) == 0
+}
+
+/// CMAC (OMAC1) as in NIST SP 800-38B / RFC 4493.
+///
+/// Supports 64 & 128 bit block ciphers.
+#[derive(Debug)]
+pub struct Cmac<C: BlockCipher<B, Unit = u8>, const B: usize> {
+    key: Secret<Vec<u8>>,
+    k1: Secret<[u8; B]>,
+    k2: Secret<[u8; B]>,
+    state: Secret<[u8; B]>,
+    // last block is held back until finalize, as it is xored with K1 or K2
+    buffer: Secret<Vec<u8>>,
+    _marker_c: PhantomData<C>,
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize> Cmac<C, B> {
+    // K1 = dbl(L), K2 = dbl(K1) where L = Cipher(0^B)
+    pub(crate) fn subkeys<K: AsRef<[u8]>>(key: K) -> (Secret<[u8; B]>, Secret<[u8; B]>) {
+        let mut l = Secret::new([0; B]);
+        C::cipher_in_place(&mut l, key.as_ref());
+        let k1 = Secret::new(dbl(&l));
+        let k2 = Secret::new(dbl(&k1));
+        (k1, k2)
+    }
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize> Mac for Cmac<C, B> {
+    const TAG_LEN: usize = B;
+
+    fn new_mac<K: AsRef<[u8]>>(key: K) -> Self {
+        let (k1, k2) = Self::subkeys(key.as_ref());
+        Cmac {
+            key: Secret::new(key.as_ref().to_vec()),
+            k1,
+            k2,
+            state: Secret::new([0; B]),
+            buffer: Secret::new(Vec::with_capacity(B)),
+            _marker_c: PhantomData,
+        }
+    }
+
+    fn update<T: AsRef<[u8]>>(&mut self, data: T) {
+        for &byte in data.as_ref() {
+            if self.buffer.len() == B {
+                cbc_step::<C, B>(&mut self.state, &self.buffer, &self.key);
+                self.buffer.clear();
+            }
+            self.buffer.push(byte);
+        }
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let mut last = Secret::new([0u8; B]);
+        let len = self.buffer.len();
+        last[..len].copy_from_slice(&self.buffer);
+        let subkey = if len == B {
+            &self.k1
+        } else {
+            // 10* padding
+            last[len] = 0x80;
+            &self.k2
+        };
+        last.iter_mut()
+            .zip(subkey.iter())
+            .for_each(|(a, b)| *a ^= b);
+
+        cbc_step::<C, B>(&mut self.state, &*last, &self.key);
+        self.state.to_vec()
+    }
+}
+
+/// Raw CBC-MAC, the last block of the CBC encryption of the `D` padded message.
+///
+/// Only secure for fixed length messages under a zero IV, with a caller chosen
+/// IV it is trivially forgeable. Meant for studying those forgeries.
+#[derive(Debug)]
+pub struct CbcMac<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> {
+    key: Secret<Vec<u8>>,
+    state: Secret<[u8; B]>,
+    buffer: Secret<Vec<u8>>,
+    _marker_c: PhantomData<C>,
+    _marker_d: PhantomData<D>,
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> CbcMac<C, B, D> {
+    pub fn with_iv<K: AsRef<[u8]>>(key: K, iv: [u8; B]) -> Self {
+        CbcMac {
+            key: Secret::new(key.as_ref().to_vec()),
+            state: Secret::new(iv),
+            buffer: Secret::new(Vec::with_capacity(B)),
+            _marker_c: PhantomData,
+            _marker_d: PhantomData,
+        }
+    }
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> Mac for CbcMac<C, B, D> {
+    const TAG_LEN: usize = B;
+
+    fn new_mac<K: AsRef<[u8]>>(key: K) -> Self {
+        Self::with_iv(key, [0; B])
+    }
+
+    fn update<T: AsRef<[u8]>>(&mut self, data: T) {
+        for &byte in data.as_ref() {
+            self.buffer.push(byte);
+            if self.buffer.len() == B {
+                cbc_step::<C, B>(&mut self.state, &self.buffer, &self.key);
+                self.buffer.clear();
+            }
+        }
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        // all full blocks are already processed, padding only depends on the tail
+        let padded = Secret::new(D::add_pad::<B>(self.buffer.to_vec()));
+        for block in padded.chunks_exact(B) {
+            cbc_step::<C, B>(&mut self.state, block, &self.key);
+        }
+        self.state.to_vec()
+    }
+}
+
+//
+// Private Functions
+//
+
+// state = Cipher(state ^ block)
+fn cbc_step<C: BlockCipher<B, Unit = u8>, const B: usize>(
+    state: &mut [u8; B],
+    block: &[u8],
+    key: &[u8],
+) {
+    state.iter_mut().zip(block).for_each(|(a, b)| *a ^= b);
+    C::cipher_in_place(state, key);
+}
+
+// multiplication by x in GF(2^64) or GF(2^128), without branching on the msb
+fn dbl<const B: usize>(block: &[u8; B]) -> [u8; B] {
+    let rb = match B {
+        16 => 0x87,
+        8 => 0x1b,
+        _ => panic!("CMAC: unsupported block size {B}"),
+    };
+
+    let mut out = [0u8; B];
+    for i in 0..B {
+        let carry = if i + 1 < B { block[i + 1] >> 7 } else { 0 };
+        out[i] = (block[i] << 1) | carry;
+    }
+    out[B - 1] ^= 0u8.wrapping_sub(block[0] >> 7) & rb;
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ct_eq, Mac};
+    use crate::{Aes128CBC, Aes128CbcMac, Aes128Cmac, Modes};
+    use hex::ToHex;
+
+    const RFC4493_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const RFC4493_MSG: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                               30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    #[test]
+    fn test_cmac_subkeys() {
+        // RFC 4493 section 4, subkey generation
+        let key = RFC4493_KEY.to_hex().unwrap();
+        let (k1, k2) = Aes128Cmac::subkeys(&key);
+        assert_eq!(
+            hex::encode(*k1).unwrap(),
+            "fbeed618357133667c85e08f7236a8de"
+        );
+        assert_eq!(
+            hex::encode(*k2).unwrap(),
+            "f7ddac306ae266ccf90bc11ee46d513b"
+        );
+    }
+
+    #[test]
+    fn test_cmac_incremental() {
+        let key = RFC4493_KEY.to_hex().unwrap();
+        let msg = RFC4493_MSG.to_hex().unwrap();
+
+        for len in [0, 16, 40, 64] {
+            let one_shot = Aes128Cmac::mac(&key, &msg.data()[..len]);
+            for step in [1, 3, 16, 17] {
+                let mut mac = Aes128Cmac::new_mac(&key);
+                msg.data()[..len].chunks(step).for_each(|c| mac.update(c));
+                assert!(mac.verify(&one_shot), "len={len} step={step}");
+            }
+        }
+
+        let mut mac = Aes128Cmac::new_mac(&key);
+        mac.update(&msg.data()[..40]);
+        assert!(!mac.verify("dfa66747de9ae63030ca32611497c826".to_hex().unwrap()));
+    }
+
+    #[test]
+    fn test_cbc_mac() {
+        let key = b"YELLOW SUBMARINE";
+        let iv = [7u8; 16];
+        let msg = b"alert('MZA who was that?');\n";
+
+        // last block of the CBC encryption
+        let enc = Aes128CBC::with_iv(iv.to_vec()).encrypt(msg, key).unwrap();
+        let mut mac = Aes128CbcMac::with_iv(key, iv);
+        msg.chunks(5).for_each(|c| mac.update(c));
+        assert_eq!(mac.finalize(), &enc[enc.len() - 16..]);
+
+        // block aligned message gets a full block of padding
+        let enc = Aes128CBC::new().encrypt([1; 32], key).unwrap();
+        assert_eq!(enc.len(), 48);
+        assert_eq!(Aes128CbcMac::mac(key, [1; 32]), &enc[32..]);
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"YELLOW SUBMARINE", b"YELLOW SUBMARINE"));
+        assert!(!ct_eq(b"YELLOW SUBMARINE", b"YELLOW SUBMARINF"));
+        assert!(!ct_eq(b"YELLOW", b"YELLOW SUBMARINE"));
+        assert!(ct_eq(b"", b""));
+    }
+}
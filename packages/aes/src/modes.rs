@@ -1,6 +1,10 @@
-use std::marker::PhantomData;
+use std::{cell::RefCell, marker::PhantomData};
 
-use crate::{cipher::BlockCipher, padding::CipherPadding, utils::to_arr};
+use crate::{
+    cipher::BlockCipher,
+    padding::{CipherPadding, NoPadding},
+    secret::Secret,
+};
 
 pub trait Modes<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> {
     // Assumes plain_text to be properly padded, otherwise panic
@@ -16,6 +20,11 @@ pub trait Modes<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding>
         cipher_text: U,
         key: T,
     ) -> anyhow::Result<Vec<u8>>;
+
+    // IV used by the mode, empty for modes without one
+    fn iv(&self) -> &[u8] {
+        &[]
+    }
 }
 
 #[derive(Debug)]
@@ -35,7 +44,9 @@ impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> EcbMode<C,
 
 #[derive(Debug)]
 pub struct CbcMode<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> {
-    iv: Vec<u8>,
+    iv: Secret<Vec<u8>>,
+    // the key doubles as IV, insecure but found in the wild
+    key_as_iv: bool,
     _marker_c: PhantomData<C>,
     _marker_d: PhantomData<D>,
 }
@@ -44,7 +55,8 @@ impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> CbcMode<C,
     pub const DEFAULT_CBC_IV: [u8; B] = [0; B];
     pub fn new() -> Self {
         CbcMode {
-            iv: Self::DEFAULT_CBC_IV.to_vec(),
+            iv: Secret::new(Self::DEFAULT_CBC_IV.to_vec()),
+            key_as_iv: false,
             _marker_c: PhantomData,
             _marker_d: PhantomData,
         }
@@ -52,11 +64,156 @@ impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> CbcMode<C,
 
     pub fn with_iv(iv: Vec<u8>) -> Self {
         CbcMode {
-            iv,
+            iv: Secret::new(iv),
+            key_as_iv: false,
+            _marker_c: PhantomData,
+            _marker_d: PhantomData,
+        }
+    }
+
+    /// Use the key as IV, as some legacy systems do. Only meant for studying
+    /// the key recovery it allows (cryptopals challenge 27), the key must be
+    /// exactly one block long.
+    pub fn with_key_as_iv() -> Self {
+        CbcMode {
+            iv: Secret::new(vec![]),
+            key_as_iv: true,
             _marker_c: PhantomData,
             _marker_d: PhantomData,
         }
     }
+
+    fn iv_for<'a>(&'a self, key: &'a [u8]) -> anyhow::Result<&'a [u8]> {
+        let iv = if self.key_as_iv { key } else { &self.iv };
+        if iv.len() != B {
+            anyhow::bail!("CBC: IV should be {B} bytes, got {}", iv.len());
+        }
+        Ok(iv)
+    }
+}
+
+/// CBC carrying the IV across records, each record is encrypted under the
+/// last ciphertext block of the previous one (SSL 3.0 / TLS 1.0).
+///
+/// Both peers keep their own instance, which stay in sync as long as every
+/// record is decrypted in order. Only meant for simulating attacks on
+/// predictable IVs.
+#[derive(Debug)]
+pub struct ChainedCbcMode<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> {
+    iv: Secret<Vec<u8>>,
+    next_iv: RefCell<Secret<Vec<u8>>>,
+    _marker_c: PhantomData<C>,
+    _marker_d: PhantomData<D>,
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> ChainedCbcMode<C, B, D> {
+    pub fn with_iv(iv: Vec<u8>) -> Self {
+        ChainedCbcMode {
+            next_iv: RefCell::new(Secret::new(iv.clone())),
+            iv: Secret::new(iv),
+            _marker_c: PhantomData,
+            _marker_d: PhantomData,
+        }
+    }
+
+    // IV of the next record
+    pub fn next_iv(&self) -> Vec<u8> {
+        self.next_iv.borrow().to_vec()
+    }
+
+    fn record_mode(&self) -> CbcMode<C, B, D> {
+        CbcMode::with_iv(self.next_iv.borrow().to_vec())
+    }
+
+    fn chain(&self, cipher_text: &[u8]) {
+        if let Some(last) = cipher_text.chunks_exact(B).last() {
+            *self.next_iv.borrow_mut() = Secret::new(last.to_vec());
+        }
+    }
+}
+
+/// CTR mode, keystream blocks are `Cipher(nonce || counter)` with a 64 bit
+/// little endian block counter (as in cryptopals), so the nonce is `B - 8` bytes.
+#[derive(Debug)]
+pub struct CtrMode<C: BlockCipher<B, Unit = u8>, const B: usize> {
+    nonce: Secret<Vec<u8>>,
+    _marker_c: PhantomData<C>,
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize> CtrMode<C, B> {
+    pub fn new() -> Self {
+        Self::with_nonce(vec![0; B - 8])
+    }
+
+    pub fn with_nonce(nonce: Vec<u8>) -> Self {
+        CtrMode {
+            nonce: Secret::new(nonce),
+            _marker_c: PhantomData,
+        }
+    }
+
+    /// `data ^ keystream[offset..]`, encrypts or decrypts data found at byte
+    /// `offset` of a stream, without touching the blocks before it.
+    pub fn apply_keystream_at<T: AsRef<[u8]>, K: AsRef<[u8]>>(
+        &self,
+        data: T,
+        key: K,
+        offset: usize,
+    ) -> anyhow::Result<Vec<u8>> {
+        if self.nonce.len() + 8 != B {
+            anyhow::bail!(
+                "CTR: nonce should be {} bytes, got {}",
+                B.saturating_sub(8),
+                self.nonce.len()
+            );
+        }
+
+        let (data, key) = (data.as_ref(), key.as_ref());
+        let mut applied = Vec::with_capacity(data.len());
+        let (mut block, mut skip) = (offset / B, offset % B);
+        while applied.len() < data.len() {
+            // Cipher(nonce || counter)
+            let mut keystream = Secret::new([0u8; B]);
+            keystream[..B - 8].copy_from_slice(&self.nonce);
+            keystream[B - 8..].copy_from_slice(&(block as u64).to_le_bytes());
+            C::cipher_in_place(&mut keystream, key);
+
+            let chunk = &data[applied.len()..data.len().min(applied.len() + B - skip)];
+            applied.extend(chunk.iter().zip(&keystream[skip..]).map(|(a, b)| a ^ b));
+            (block, skip) = (block + 1, 0);
+        }
+        Ok(applied)
+    }
+
+    /// Replace the plaintext at `offset` of `cipher_text` with `new_text`,
+    /// only re-encrypting the edited bytes (the stream may grow).
+    pub fn edit<U: AsRef<[u8]>, K: AsRef<[u8]>, P: AsRef<[u8]>>(
+        &self,
+        cipher_text: U,
+        key: K,
+        offset: usize,
+        new_text: P,
+    ) -> anyhow::Result<Vec<u8>> {
+        let cipher_text = cipher_text.as_ref();
+        if offset > cipher_text.len() {
+            anyhow::bail!(
+                "CTR: edit offset {offset} past the end of the cipher text ({})",
+                cipher_text.len()
+            );
+        }
+
+        let edited = self.apply_keystream_at(new_text, key, offset)?;
+        let mut out = cipher_text.to_vec();
+        out.resize(cipher_text.len().max(offset + edited.len()), 0);
+        out[offset..offset + edited.len()].copy_from_slice(&edited);
+        Ok(out)
+    }
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize> Default for CtrMode<C, B> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> Modes<C, B, D>
@@ -68,17 +225,15 @@ impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> Modes<C, B,
         key: T,
     ) -> anyhow::Result<Vec<u8>> {
         // add padding
-        let padded = D::add_pad::<B>(plain_text.as_ref().to_vec());
+        let padded = pad::<D, B>(plain_text.as_ref());
         // apply cipher in ecb mode
-        let encrypted = padded
-            .chunks_exact(B)
-            .flat_map(|chk| {
-                // safe to unwarp
-                // copy data here
-                let block = *to_arr(chk).unwrap();
-                C::cipher(block, key.as_ref())
-            })
-            .collect();
+        let mut encrypted = Vec::with_capacity(padded.len());
+        let mut block = Secret::new([0u8; B]);
+        for chk in padded.chunks_exact(B) {
+            block.copy_from_slice(chk);
+            C::cipher_in_place(&mut block, key.as_ref());
+            encrypted.extend_from_slice(&*block);
+        }
 
         Ok(encrypted)
     }
@@ -88,16 +243,14 @@ impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> Modes<C, B,
         cipher_text: U,
         key: T,
     ) -> anyhow::Result<Vec<u8>> {
-        let decrypted: Vec<u8> = cipher_text
-            .as_ref()
-            .chunks_exact(B)
-            .flat_map(|chk| {
-                // safe to unwarp
-                // copy data here
-                let block = *to_arr(chk).unwrap();
-                C::inv_cipher(block, key.as_ref())
-            })
-            .collect();
+        let cipher_text = cipher_text.as_ref();
+        let mut decrypted = Vec::with_capacity(cipher_text.len());
+        let mut block = Secret::new([0u8; B]);
+        for chk in cipher_text.chunks_exact(B) {
+            block.copy_from_slice(chk);
+            C::inv_cipher_in_place(&mut block, key.as_ref());
+            decrypted.extend_from_slice(&*block);
+        }
 
         // remove padding
         Ok(D::remove_pad::<B>(decrypted))
@@ -112,32 +265,19 @@ impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> Modes<C, B,
         plain_text: P,
         key: T,
     ) -> anyhow::Result<Vec<u8>> {
+        let iv = self.iv_for(key.as_ref())?;
         // add padding
-        let padded = D::add_pad::<B>(plain_text.as_ref().to_vec());
-        let padded_len = padded.len();
-        let encrypted = padded
-            // get chunks of block size
-            .chunks_exact(B)
-            // apply cbc mode
-            .fold(Vec::with_capacity(padded_len / B), |mut agg, p1| {
-                // CBC Encrypt
-                // C[i] = Cipher(P[i] ^ P[i-1])
-
-                let c0 = agg.last().unwrap_or(&self.iv);
-                // println!("p0                - {p0:?}");
-                // println!("p1                - {p1:?}");
-
-                // xor chunk (p0 ^ p1)
-                let xored = c0.iter().zip(p1).map(|(a, b)| a ^ b).collect::<Vec<_>>();
-                // println!("p0 ^ p1           - {xored:?}");
-                let block = C::cipher(*to_arr(&xored).unwrap(), key.as_ref());
-                // println!("Cipher(p0 ^ p1)   - {block:?}");
-                agg.push(block.to_vec());
-                agg
-            })
-            .into_iter()
-            .flatten()
-            .collect();
+        let padded = pad::<D, B>(plain_text.as_ref());
+        let mut encrypted = Vec::with_capacity(padded.len());
+        // CBC Encrypt
+        // C[i] = Cipher(P[i] ^ C[i-1]), chained in place from the IV
+        let mut block = Secret::new([0u8; B]);
+        block.copy_from_slice(iv);
+        for p1 in padded.chunks_exact(B) {
+            block.iter_mut().zip(p1).for_each(|(c0, p1)| *c0 ^= p1);
+            C::cipher_in_place(&mut block, key.as_ref());
+            encrypted.extend_from_slice(&*block);
+        }
         Ok(encrypted)
     }
 
@@ -146,43 +286,89 @@ impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> Modes<C, B,
         cipher_text: U,
         key: T,
     ) -> anyhow::Result<Vec<u8>> {
-        let decrypted = [self.iv.as_slice(), cipher_text.as_ref()]
-            .concat()
-            // get chunks of block size
-            .chunks_exact(B)
-            .collect::<Vec<&[u8]>>()
-            .windows(2)
-            // apply cbc mode
-            .flat_map(|win| {
-                // CBC Decrypt
-                // P[i] = InvCipher(C[i]) ^ C[i-1]
-
-                // get the P[i-1] or in case of i=0, the IV
-                let c0 = win[0];
-                let c1 = win[1];
-
-                // println!("p0                - {p0:?}");
-                // println!("c1                - {c1:?}");
-
-                // apply inv cipher
-                let block = C::inv_cipher(*to_arr(c1).unwrap(), key.as_ref());
-                // println!("InCipher(c1)      - {block:?}");
-
-                // xor chunk (c0 ^ InvCipher(c1))
-                let xored = c0
-                    .iter()
-                    .zip(&block)
-                    .map(|(a, b)| a ^ b)
-                    .collect::<Vec<_>>();
-                // println!("c0 ^ InCipher(c1) - {xored:?}");
-
-                xored
-            })
-            .collect();
+        let iv = self.iv_for(key.as_ref())?;
+        let cipher_text = cipher_text.as_ref();
+        let mut decrypted = Vec::with_capacity(cipher_text.len());
+        // CBC Decrypt
+        // P[i] = InvCipher(C[i]) ^ C[i-1], chained from the IV in place as it
+        // may be the key
+        let previous = iv.chunks_exact(B).chain(cipher_text.chunks_exact(B));
+        let mut block = Secret::new([0u8; B]);
+        for (c0, c1) in previous.zip(cipher_text.chunks_exact(B)) {
+            block.copy_from_slice(c1);
+            C::inv_cipher_in_place(&mut block, key.as_ref());
+            block.iter_mut().zip(c0).for_each(|(b, c0)| *b ^= c0);
+            decrypted.extend_from_slice(&*block);
+        }
 
         // remove padding
         Ok(D::remove_pad::<B>(decrypted))
     }
+
+    fn iv(&self) -> &[u8] {
+        &self.iv
+    }
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize, D: CipherPadding> Modes<C, B, D>
+    for ChainedCbcMode<C, B, D>
+{
+    fn encrypt<P: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        plain_text: P,
+        key: T,
+    ) -> anyhow::Result<Vec<u8>> {
+        let cipher_text = self.record_mode().encrypt(plain_text, key)?;
+        self.chain(&cipher_text);
+        Ok(cipher_text)
+    }
+
+    fn decrypt<U: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        cipher_text: U,
+        key: T,
+    ) -> anyhow::Result<Vec<u8>> {
+        let plain_text = self.record_mode().decrypt(cipher_text.as_ref(), key)?;
+        self.chain(cipher_text.as_ref());
+        Ok(plain_text)
+    }
+
+    // IV of the first record
+    fn iv(&self) -> &[u8] {
+        &self.iv
+    }
+}
+
+impl<C: BlockCipher<B, Unit = u8>, const B: usize> Modes<C, B, NoPadding> for CtrMode<C, B> {
+    fn encrypt<P: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        plain_text: P,
+        key: T,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.apply_keystream_at(plain_text, key, 0)
+    }
+
+    fn decrypt<U: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        cipher_text: U,
+        key: T,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.apply_keystream_at(cipher_text, key, 0)
+    }
+
+    fn iv(&self) -> &[u8] {
+        &self.nonce
+    }
+}
+
+// Private Functions
+
+// padded copy of the plain text, allocated at its final capacity so that
+// adding the padding never reallocates and leaves an unzeroed copy behind
+fn pad<D: CipherPadding, const B: usize>(plain_text: &[u8]) -> Secret<Vec<u8>> {
+    let mut data = Vec::with_capacity(plain_text.len() + B);
+    data.extend_from_slice(plain_text);
+    Secret::new(D::add_pad::<B>(data))
 }
 
 // fn a() {
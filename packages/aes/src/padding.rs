@@ -21,6 +21,31 @@ impl CipherPadding for PKCS7Padding {
 
 pub struct PKCS7Padding;
 
+impl PKCS7Padding {
+    // strict check, `remove_pad` trusts the last byte
+    pub fn is_valid<const B: usize>(data: &[u8]) -> bool {
+        match data.last() {
+            Some(&p) if (1..=B).contains(&(p as usize)) && data.len().is_multiple_of(B) => {
+                data[data.len() - p as usize..].iter().all(|&b| b == p)
+            }
+            _ => false,
+        }
+    }
+}
+
+// for stream modes (CTR), which never pad
+impl CipherPadding for NoPadding {
+    fn add_pad<const B: usize>(data: Vec<u8>) -> Vec<u8> {
+        data
+    }
+
+    fn remove_pad<const B: usize>(data: Vec<u8>) -> Vec<u8> {
+        data
+    }
+}
+
+pub struct NoPadding;
+
 #[cfg(test)]
 mod test {
     use super::{CipherPadding, PKCS7Padding};
@@ -45,6 +70,26 @@ mod test {
         assert_eq!(unpadded, data);
     }
 
+    #[test]
+    fn test_pkcs7_padding_is_valid() {
+        assert!(PKCS7Padding::is_valid::<16>(
+            b"ICE ICE BABY\x04\x04\x04\x04"
+        ));
+        assert!(!PKCS7Padding::is_valid::<16>(
+            b"ICE ICE BABY\x05\x05\x05\x05"
+        ));
+        assert!(!PKCS7Padding::is_valid::<16>(
+            b"ICE ICE BABY\x01\x02\x03\x04"
+        ));
+        assert!(!PKCS7Padding::is_valid::<16>(
+            b"ICE ICE BABY\x00\x00\x00\x00"
+        ));
+        assert!(!PKCS7Padding::is_valid::<16>(b"ICE ICE BABY\x01"));
+        assert!(PKCS7Padding::is_valid::<16>(&[16; 16]));
+        assert!(!PKCS7Padding::is_valid::<16>(&[17; 16]));
+        assert!(!PKCS7Padding::is_valid::<16>(&[]));
+    }
+
     #[test]
     fn test_pkcs7_padding_remove_more_than_buffer() {
         let padded = vec![1,3];
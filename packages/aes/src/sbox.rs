@@ -1,39 +1,44 @@
-// pub static SBOX_TABLE: [u8; 256] = calculate_aes_sbox();
-
-#[inline(always)]
-const fn rotl8(x: u8, shift: u8) -> u8 {
-    ((x) << (shift)) | ((x) >> (8 - (shift)))
-}
+use gf256::Rijndael;
 
+// S-box is the multiplicative inverse in GF(2^8) followed by the affine
+// transformation b ^ rotl(b, 1) ^ rotl(b, 2) ^ rotl(b, 3) ^ rotl(b, 4) ^ 0x63
+// (FIPS-197 section 5.1.1).
 pub const fn calculate_aes_sbox() -> [u8; 256] {
     let mut table: [u8; 256] = [0_u8; 256];
 
-    let mut p = 1u8;
-    let mut q = 1u8;
+    let mut i = 0;
+    while i < 256 {
+        let b = Rijndael::new(i as u8).inv().value();
+        table[i] =
+            b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63;
+        i += 1;
+    }
 
-    /* loop invariant: p * q == 1 in the Galois field */
-    loop {
-        /* multiply p by 3 */
-        p = p ^ (p << 1) ^ (if (p & 0x80) != 0x0 { 0x1B } else { 0 });
+    table
+}
 
-        /* divide q by 3 (equals multiplication by 0xf6) */
-        q ^= q << 1;
-        q ^= q << 2;
-        q ^= q << 4;
-        q ^= if (q & 0x80) != 0x0 { 0x09 } else { 0 };
+pub const fn calculate_aes_inv_sbox() -> [u8; 256] {
+    let sbox = calculate_aes_sbox();
+    let mut table: [u8; 256] = [0_u8; 256];
 
-        /* compute the affine transformation */
-        let xformed = q ^ rotl8(q, 1) ^ rotl8(q, 2) ^ rotl8(q, 3) ^ rotl8(q, 4);
+    let mut i = 0;
+    while i < 256 {
+        table[sbox[i] as usize] = i as u8;
+        i += 1;
+    }
 
-        table[p as usize] = xformed ^ 0x63;
+    table
+}
 
-        if p == 1 {
-            break;
-        }
-    }
+// rc[i] = x^(i-1) in GF(2^8), (FIPS-197 section 5.2)
+pub const fn calculate_aes_rcon<const N: usize>() -> [u8; N] {
+    let mut table: [u8; N] = [0_u8; N];
 
-    /* 0 is a special case since it has no inverse */
-    table[0] = 0x63;
+    let mut i = 0;
+    while i < N {
+        table[i] = Rijndael::new(0x02).pow(i as u32).value();
+        i += 1;
+    }
 
     table
 }
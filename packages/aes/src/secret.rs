@@ -0,0 +1,116 @@
+use std::{
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+    ptr,
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+/// Types whose memory can be securely wiped.
+pub trait Zeroize {
+    fn zeroize(&mut self);
+}
+
+// volatile writes so that the compiler can not elide the zeroing of memory
+// which is never read again.
+fn volatile_zero(ptr: *mut u8, len: usize) {
+    for i in 0..len {
+        // SAFETY: caller guarantees `ptr..ptr+len` is valid, writable memory
+        unsafe { ptr::write_volatile(ptr.add(i), 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+impl<const N: usize> Zeroize for [u8; N] {
+    fn zeroize(&mut self) {
+        volatile_zero(self.as_mut_ptr(), N);
+    }
+}
+
+impl Zeroize for Vec<u8> {
+    fn zeroize(&mut self) {
+        // wipe the whole allocation, including the spare capacity which may
+        // still hold bytes from an earlier `truncate`.
+        volatile_zero(self.as_mut_ptr(), self.capacity());
+        self.clear();
+    }
+}
+
+/// Owned secret (keys, round keys, intermediate blocks) which is zeroed when
+/// dropped and never printed by `Debug`.
+#[derive(Clone, Default)]
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> Debug for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Secret([REDACTED])")
+    }
+}
+
+impl<T: Zeroize> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> DerefMut for Secret<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroize + AsRef<[u8]>> AsRef<[u8]> for Secret<T> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Secret, Zeroize};
+
+    #[test]
+    fn test_zeroize_vec_capacity() {
+        let mut key = b"YELLOW SUBMARINE".to_vec();
+        key.truncate(4);
+        key.zeroize();
+        assert!(key.is_empty());
+        // spare capacity is wiped as well
+        let spare = unsafe { std::slice::from_raw_parts(key.as_ptr(), 16) };
+        assert_eq!(spare, [0; 16]);
+    }
+
+    #[test]
+    fn test_zeroize_array() {
+        let mut block = [0xaa; 16];
+        block.zeroize();
+        assert_eq!(block, [0; 16]);
+    }
+
+    #[test]
+    fn test_secret_debug_redacted() {
+        let key = Secret::new(b"YELLOW SUBMARINE".to_vec());
+        let dbg = format!("{key:?}");
+        assert_eq!(dbg, "Secret([REDACTED])");
+        assert_eq!(key.as_ref(), b"YELLOW SUBMARINE");
+    }
+}
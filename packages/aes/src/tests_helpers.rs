@@ -3,9 +3,10 @@ use hex::{Hex, ToHex};
 
 use crate::{
     cipher::BlockCipher,
+    mac::Mac,
     padding::CipherPadding,
     utils::to_arr,
-    Aes128CBC, Aes192CBC, Aes256CBC, Modes,
+    Aes128CBC, Aes128Cmac, Aes192CBC, Aes192Cmac, Aes256CBC, Aes256Cmac, Modes,
     {cipher::AesCipher, Aes128, Aes192, Aes256},
 };
 
@@ -44,6 +45,10 @@ pub enum Directive {
         block: Hex,
         key_sch: Hex,
     },
+    Cmac {
+        key: Hex,
+        message: Hex,
+    },
 }
 
 impl Directive {
@@ -156,6 +161,12 @@ impl Directive {
                 AesCipher128::add_round_key(&mut _block, _key_sch);
                 Ok(hex::encode(_block)? + "\n")
             }
+            Directive::Cmac { key, message } => match key.data().len() * 8 {
+                128 => Ok(hex::encode(Aes128Cmac::mac(key, message))? + "\n"),
+                192 => Ok(hex::encode(Aes192Cmac::mac(key, message))? + "\n"),
+                256 => Ok(hex::encode(Aes256Cmac::mac(key, message))? + "\n"),
+                k => anyhow::bail!("Invalid key length - {}", k),
+            },
         }
     }
 }
@@ -256,6 +267,20 @@ impl TryFrom<TestCase> for Directive {
                 .to_hex()?;
                 Ok(Self::AddRoundKey { block, key_sch })
             }
+            "cmac" => {
+                let message = remove_whitespace(&case.input).to_hex()?;
+                let key = remove_whitespace(
+                    case.args
+                        .get("key")
+                        .ok_or(anyhow::anyhow!(
+                            "key arg missing in cmac directive test case"
+                        ))?
+                        .get(0)
+                        .ok_or(anyhow::anyhow!("key is empty"))?,
+                )
+                .to_hex()?;
+                Ok(Self::Cmac { key, message })
+            }
             u => Err(anyhow::anyhow!("Unknown directive - {}", u)),
         }
     }
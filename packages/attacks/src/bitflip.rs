@@ -0,0 +1,170 @@
+use blockanalysis::{detect_block_size, detect_oracle_mode, detect_prefix_len, BlockMode};
+
+// attacker input, flipped into the target afterwards
+const FILLER: u8 = b'A';
+
+/// Where the oracle puts the attacker input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    // CBC with block size `block_size`, flipping a ciphertext block scrambles
+    // its own plaintext block and flips the same bits of the next one
+    Cbc {
+        block_size: usize,
+        prefix_len: usize,
+    },
+    // CTR (or any stream cipher), flipping a ciphertext bit flips the same
+    // plaintext bit and nothing else
+    Stream {
+        prefix_len: usize,
+    },
+}
+
+/// Measure an oracle computing `Enc(prefix || quote(input) || suffix)`.
+///
+/// The oracle must be deterministic (fixed IV or nonce per key), the filler
+/// input is never touched by quoting.
+pub fn detect_layout<F: Fn(&[u8]) -> Vec<u8>>(oracle: F) -> anyhow::Result<Layout> {
+    if oracle(&[FILLER]).len() == oracle(&[]).len() + 1 {
+        // byte granular prefix detection is the same as a block size of one
+        return Ok(Layout::Stream {
+            prefix_len: detect_prefix_len(&oracle, 1)?,
+        });
+    }
+
+    let block_size = detect_block_size(&oracle)?;
+    if detect_oracle_mode(&oracle, block_size) != BlockMode::Cbc {
+        anyhow::bail!("bitflip: oracle is neither CBC nor a stream mode");
+    }
+    Ok(Layout::Cbc {
+        block_size,
+        prefix_len: detect_prefix_len(&oracle, block_size)?,
+    })
+}
+
+/// Ciphertext which decrypts with `target` in place of attacker input, e.g.
+/// `;admin=true;` which the oracle itself would have quoted (cryptopals
+/// challenges 16 & 26).
+///
+/// For CBC the block before the target decrypts to garbage, so the target
+/// must fit in a single block.
+pub fn inject<F: Fn(&[u8]) -> Vec<u8>>(oracle: F, target: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let layout = detect_layout(&oracle)?;
+    let (input, target_at, flip_at) = match layout {
+        Layout::Stream { prefix_len } => (target.len(), prefix_len, prefix_len),
+        Layout::Cbc {
+            block_size,
+            prefix_len,
+        } => {
+            if target.len() > block_size {
+                anyhow::bail!(
+                    "bitflip: target ({} bytes) longer than a block ({block_size})",
+                    target.len()
+                );
+            }
+            // fill up the prefix block, one block to scramble, then the target
+            let pad = (block_size - prefix_len % block_size) % block_size;
+            let scrambled_at = prefix_len + pad;
+            (
+                pad + block_size + target.len(),
+                scrambled_at + block_size,
+                scrambled_at,
+            )
+        }
+    };
+
+    let mut cipher_text = oracle(&vec![FILLER; input]);
+    if cipher_text.len() < target_at + target.len() {
+        anyhow::bail!("bitflip: oracle output shorter than its input");
+    }
+    cipher_text[flip_at..flip_at + target.len()]
+        .iter_mut()
+        .zip(target)
+        .for_each(|(c, t)| *c ^= FILLER ^ t);
+    Ok(cipher_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128CBC, Aes128Ctr, Modes};
+
+    const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+    const PREFIX: &str = "comment1=cooking%20MCs;userdata=";
+    const SUFFIX: &str = ";comment2=%20like%20a%20pound%20of%20bacon";
+
+    fn quote(input: &[u8]) -> Vec<u8> {
+        input
+            .iter()
+            .flat_map(|&b| match b {
+                b';' => b"%3B".to_vec(),
+                b'=' => b"%3D".to_vec(),
+                b => vec![b],
+            })
+            .collect()
+    }
+
+    fn is_admin(plain_text: &[u8]) -> bool {
+        String::from_utf8_lossy(plain_text).contains(";admin=true;")
+    }
+
+    #[test]
+    fn test_cbc_bitflip() {
+        for prefix in ["", "a", PREFIX, "comment1=cooking%20MCs;user="] {
+            let oracle = |input: &[u8]| {
+                let plain_text = [prefix.as_bytes(), &quote(input), SUFFIX.as_bytes()].concat();
+                Aes128CBC::with_iv(vec![3; 16])
+                    .encrypt(plain_text, KEY)
+                    .unwrap()
+            };
+            assert!(!is_admin(
+                &Aes128CBC::with_iv(vec![3; 16])
+                    .decrypt(oracle(b";admin=true;"), KEY)
+                    .unwrap()
+            ));
+
+            assert_eq!(
+                detect_layout(oracle).unwrap(),
+                Layout::Cbc {
+                    block_size: 16,
+                    prefix_len: prefix.len()
+                }
+            );
+            let forged = inject(oracle, b";admin=true;").unwrap();
+            let plain_text = Aes128CBC::with_iv(vec![3; 16])
+                .decrypt(forged, KEY)
+                .unwrap();
+            assert!(is_admin(&plain_text));
+            assert!(plain_text.starts_with(prefix.as_bytes()));
+            assert!(plain_text.ends_with(SUFFIX.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_ctr_bitflip() {
+        let oracle = |input: &[u8]| {
+            let plain_text = [PREFIX.as_bytes(), &quote(input), SUFFIX.as_bytes()].concat();
+            Aes128Ctr::new().encrypt(plain_text, KEY).unwrap()
+        };
+        assert_eq!(
+            detect_layout(oracle).unwrap(),
+            Layout::Stream {
+                prefix_len: PREFIX.len()
+            }
+        );
+
+        // no block is scrambled, the target can be any length
+        let target = b";admin=true;role=admin;uid=0;";
+        let forged = inject(oracle, target).unwrap();
+        let plain_text = Aes128Ctr::new().decrypt(forged, KEY).unwrap();
+        assert_eq!(
+            plain_text,
+            [PREFIX.as_bytes(), target, SUFFIX.as_bytes()].concat()
+        );
+    }
+
+    #[test]
+    fn test_cbc_target_too_long() {
+        let oracle = |input: &[u8]| Aes128CBC::new().encrypt(quote(input), KEY).unwrap();
+        assert!(inject(oracle, &[b'x'; 17]).is_err());
+    }
+}
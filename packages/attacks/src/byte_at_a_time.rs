@@ -0,0 +1,222 @@
+use std::cell::Cell;
+
+use blockanalysis::{detect_block_size, detect_oracle_mode, BlockMode};
+
+// filler pushing attacker bytes to a block boundary
+const FILLER: u8 = b'A';
+// an encrypted marker block followed by the other marker block flags where
+// the attacker input starts
+const MARKER: u8 = b'M';
+const OTHER_MARKER: u8 = b'N';
+// tries at hitting the alignment, only more than one block size when the
+// prefix length changes between calls
+const MAX_ALIGN_ATTEMPTS: usize = 1024;
+
+/// ECB oracle computing `Enc(prefix || input || suffix)` with the prefix cut off.
+///
+/// Each query is sent as `A^k || M^B || N^B || input`. When `k` aligns the
+/// markers their two ciphertext blocks show up next to each other and
+/// everything after them is `Enc(input || suffix)`, whatever the prefix was.
+/// Two different markers, as a prefix ending in `M` would otherwise complete
+/// an `M^B` block one byte early.
+pub struct AlignedOracle<F> {
+    oracle: F,
+    block_size: usize,
+    // Enc(M^B)
+    marker: Vec<u8>,
+    // Enc(N^B)
+    other_marker: Vec<u8>,
+    // last filler length which aligned the markers
+    filler_len: Cell<usize>,
+}
+
+impl<F: Fn(&[u8]) -> Vec<u8>> AlignedOracle<F> {
+    pub fn new(oracle: F, block_size: usize) -> anyhow::Result<Self> {
+        // 3 marker blocks always contain 2 aligned ones, the pair which
+        // changes with the marker byte is the marker (and not the prefix)
+        let with_marker = oracle(&vec![MARKER; 3 * block_size]);
+        let with_other = oracle(&vec![OTHER_MARKER; 3 * block_size]);
+        let a = with_marker.chunks_exact(block_size).collect::<Vec<_>>();
+        let b = with_other.chunks_exact(block_size).collect::<Vec<_>>();
+
+        let find_marker = |a: &[&[u8]], b: &[&[u8]]| {
+            a.windows(2)
+                .enumerate()
+                .find(|(j, w)| w[0] == w[1] && b.get(*j) != Some(&w[0]))
+                .map(|(_, w)| w[0].to_vec())
+                .ok_or(anyhow::anyhow!(
+                    "AlignedOracle: no repeated block, oracle is not using ECB mode"
+                ))
+        };
+        let marker = find_marker(&a, &b)?;
+        let other_marker = find_marker(&b, &a)?;
+
+        Ok(AlignedOracle {
+            oracle,
+            block_size,
+            marker,
+            other_marker,
+            filler_len: Cell::new(0),
+        })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    // Enc(input || suffix)
+    pub fn query(&self, input: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let bs = self.block_size;
+        let start = self.filler_len.get();
+        for attempt in 0..MAX_ALIGN_ATTEMPTS {
+            let k = (start + attempt) % bs;
+            let query = [
+                vec![FILLER; k],
+                vec![MARKER; bs],
+                vec![OTHER_MARKER; bs],
+                input.to_vec(),
+            ]
+            .concat();
+            let cipher_text = (self.oracle)(&query);
+
+            let aligned = cipher_text
+                .chunks_exact(bs)
+                .collect::<Vec<_>>()
+                .windows(2)
+                .position(|w| w[0] == self.marker && w[1] == self.other_marker);
+            if let Some(j) = aligned {
+                self.filler_len.set(k);
+                return Ok(cipher_text[(j + 2) * bs..].to_vec());
+            }
+        }
+        anyhow::bail!("AlignedOracle: could not align the marker blocks")
+    }
+
+    /// Exact length of the unknown suffix, from the input length at which
+    /// the padded ciphertext grows by one block.
+    pub fn suffix_len(&self) -> anyhow::Result<usize> {
+        let base_len = self.query(&[])?.len();
+        for i in 1..=self.block_size {
+            if self.query(&vec![FILLER; i])?.len() > base_len {
+                return Ok(base_len - i);
+            }
+        }
+        anyhow::bail!("AlignedOracle: ciphertext length never changed, not a padded block mode")
+    }
+}
+
+/// Recover the unknown `suffix` of an ECB oracle `Enc(prefix || input || suffix)`
+/// one byte at a time (cryptopals challenges 12 & 14).
+///
+/// Block size and mode are detected from the oracle. The prefix may have any
+/// length, it is skipped with marker blocks (see [`AlignedOracle`]).
+pub fn recover_suffix<F: Fn(&[u8]) -> Vec<u8>>(oracle: F) -> anyhow::Result<Vec<u8>> {
+    let block_size = detect_block_size(&oracle)?;
+    if detect_oracle_mode(&oracle, block_size) != BlockMode::Ecb {
+        anyhow::bail!("byte_at_a_time: oracle is not using ECB mode");
+    }
+    decrypt_suffix(&AlignedOracle::new(oracle, block_size)?)
+}
+
+/// Byte at a time decryption over an already aligned oracle.
+///
+/// With `B - 1 - (n mod B)` filler bytes the next unknown byte is the last
+/// byte of a block whose other bytes are known, it is matched against the
+/// encryptions of all 256 candidates (sent together in a single query).
+/// Stops at the suffix length, before reaching the padding.
+pub fn decrypt_suffix<F: Fn(&[u8]) -> Vec<u8>>(
+    aligned: &AlignedOracle<F>,
+) -> anyhow::Result<Vec<u8>> {
+    let bs = aligned.block_size();
+    let suffix_len = aligned.suffix_len()?;
+
+    let mut known = Vec::with_capacity(suffix_len);
+    while known.len() < suffix_len {
+        let filler = vec![FILLER; bs - 1 - known.len() % bs];
+        let cipher_text = aligned.query(&filler)?;
+        let idx = (filler.len() + known.len()) / bs;
+        let target = &cipher_text[idx * bs..(idx + 1) * bs];
+
+        // last B - 1 known bytes (filler included) followed by each candidate
+        let window = [filler, known.clone()].concat();
+        let window = &window[window.len() - (bs - 1)..];
+        let dictionary_input = (0..=255u8)
+            .flat_map(|b| window.iter().copied().chain([b]))
+            .collect::<Vec<_>>();
+        let dictionary = aligned.query(&dictionary_input)?;
+
+        let byte = dictionary
+            .chunks_exact(bs)
+            .take(256)
+            .position(|block| block == target)
+            .ok_or(anyhow::anyhow!(
+                "byte_at_a_time: no match for byte {}, oracle is not deterministic",
+                known.len()
+            ))?;
+        known.push(byte as u8);
+    }
+    Ok(known)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128, Aes128CBC, Modes};
+
+    const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+    const SECRET: &[u8] = b"Rollin' in my 5.0\nWith my rag-top down so my hair can blow\n";
+
+    fn ecb_oracle(prefix: Vec<u8>) -> impl Fn(&[u8]) -> Vec<u8> {
+        move |input| {
+            Aes128::new()
+                .encrypt([prefix.as_slice(), input, SECRET].concat(), KEY)
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_recover_suffix() {
+        assert_eq!(recover_suffix(ecb_oracle(vec![])).unwrap(), SECRET);
+    }
+
+    #[test]
+    fn test_recover_suffix_with_prefix() {
+        // a prefix of repeated blocks must not be mistaken for the markers
+        for prefix_len in [1, 15, 16, 33, 47] {
+            let prefix = vec![0; prefix_len];
+            assert_eq!(recover_suffix(ecb_oracle(prefix)).unwrap(), SECRET);
+        }
+    }
+
+    #[test]
+    fn test_prefix_changing_per_call() {
+        let calls = Cell::new(0usize);
+        let oracle = |input: &[u8]| {
+            calls.set(calls.get() + 1);
+            let prefix = vec![b'p'; calls.get() * 7 % 23];
+            Aes128::new()
+                .encrypt([prefix.as_slice(), input, SECRET].concat(), KEY)
+                .unwrap()
+        };
+        let aligned = AlignedOracle::new(oracle, 16).unwrap();
+        assert_eq!(decrypt_suffix(&aligned).unwrap(), SECRET);
+    }
+
+    #[test]
+    fn test_prefix_ending_in_marker() {
+        // with B + 1 bytes the trailing `M` and 2B marker bytes used to
+        // align one byte early
+        let prefix = [vec![b'p'; 16], vec![MARKER]].concat();
+        assert_eq!(recover_suffix(ecb_oracle(prefix)).unwrap(), SECRET);
+    }
+
+    #[test]
+    fn test_cbc_rejected() {
+        let oracle = |input: &[u8]| {
+            Aes128CBC::new()
+                .encrypt([input, SECRET].concat(), KEY)
+                .unwrap()
+        };
+        assert!(recover_suffix(oracle).is_err());
+    }
+}
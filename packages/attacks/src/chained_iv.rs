@@ -0,0 +1,164 @@
+use std::cell::{Cell, RefCell};
+
+// attacker controlled bytes in front of the secret
+const FILLER: u8 = b'A';
+
+/// BEAST style attack on CBC with IVs chained across records (TLS 1.0).
+///
+/// `victim(prefix)` makes the victim send a record `head || prefix || secret`
+/// where only the length of `head` is known (e.g. `POST /` then the path),
+/// `chosen(plain_text)` makes it send a record of attacker chosen plaintext
+/// on the same connection (e.g. through a plugin). Both records are seen
+/// on the wire, so the IV of the next record is always known.
+///
+/// The prefix length puts one unknown byte at the end of a block `C[j]`.
+/// A chosen first block `P ^ C[j-1] ^ IV` encrypts to `C[j]` exactly when `P`
+/// is that block's plaintext, which tests a guess of the byte per record.
+pub struct ChainedIvAttack<V, A> {
+    victim: V,
+    chosen: A,
+    block_size: usize,
+    head_len: usize,
+    // last ciphertext block on the wire, IV of the next record
+    last_block: RefCell<Vec<u8>>,
+    records: Cell<usize>,
+}
+
+impl<V, A> ChainedIvAttack<V, A>
+where
+    V: Fn(&[u8]) -> Vec<u8>,
+    A: Fn(&[u8]) -> Vec<u8>,
+{
+    pub fn new(victim: V, chosen: A, block_size: usize, head_len: usize) -> Self {
+        ChainedIvAttack {
+            victim,
+            chosen,
+            block_size,
+            head_len,
+            last_block: RefCell::new(vec![]),
+            records: Cell::new(0),
+        }
+    }
+
+    // number of records the victim was made to send
+    pub fn records(&self) -> usize {
+        self.records.get()
+    }
+
+    /// Length of the secret, from the prefix length at which the padded
+    /// record grows by a block.
+    pub fn secret_len(&self) -> anyhow::Result<usize> {
+        let base_len = self.victim_record(&[]).len();
+        for i in 1..=self.block_size {
+            if self.victim_record(&vec![FILLER; i]).len() > base_len {
+                return (base_len - i)
+                    .checked_sub(self.head_len)
+                    .ok_or(anyhow::anyhow!(
+                        "chained_iv: head is longer than the whole record"
+                    ));
+            }
+        }
+        anyhow::bail!("chained_iv: record length never changed, not a padded block mode")
+    }
+
+    pub fn recover_secret(&self) -> anyhow::Result<Vec<u8>> {
+        let bs = self.block_size;
+        let secret_len = self.secret_len()?;
+
+        let mut known = Vec::with_capacity(secret_len);
+        while known.len() < secret_len {
+            // next unknown byte at the end of a block, with enough filler
+            // before it that the rest of the block never includes the head
+            let mut pad = bs - 1 - (self.head_len + known.len()) % bs;
+            if pad + known.len() < bs - 1 {
+                pad += bs;
+            }
+            let j = (self.head_len + pad + known.len()) / bs;
+
+            let iv = self.last_block.borrow().clone();
+            let cipher_text = self.victim_record(&vec![FILLER; pad]);
+            let prev = match j {
+                0 => iv,
+                _ => cipher_text[(j - 1) * bs..j * bs].to_vec(),
+            };
+            let target = &cipher_text[j * bs..(j + 1) * bs];
+
+            let window = [vec![FILLER; pad], known.clone()].concat();
+            let window = &window[window.len() - (bs - 1)..];
+            let byte = (0..=255u8)
+                .find(|&guess| {
+                    let next_iv = self.last_block.borrow().clone();
+                    let block = window
+                        .iter()
+                        .chain([&guess])
+                        .zip(&prev)
+                        .zip(&next_iv)
+                        .map(|((p, c), v)| p ^ c ^ v)
+                        .collect::<Vec<_>>();
+                    &self.chosen_record(&block)[..bs] == target
+                })
+                .ok_or(anyhow::anyhow!(
+                    "chained_iv: no guess matched byte {}, IVs are not chained",
+                    known.len()
+                ))?;
+            known.push(byte);
+        }
+        Ok(known)
+    }
+
+    fn victim_record(&self, prefix: &[u8]) -> Vec<u8> {
+        self.observe((self.victim)(prefix))
+    }
+
+    fn chosen_record(&self, plain_text: &[u8]) -> Vec<u8> {
+        self.observe((self.chosen)(plain_text))
+    }
+
+    fn observe(&self, cipher_text: Vec<u8>) -> Vec<u8> {
+        self.records.set(self.records.get() + 1);
+        if let Some(last) = cipher_text.chunks_exact(self.block_size).last() {
+            *self.last_block.borrow_mut() = last.to_vec();
+        }
+        cipher_text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128CBC, Aes128ChainedCBC, Modes};
+
+    const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+    const HEAD: &[u8] = b"POST /";
+    const SECRET: &[u8] = b"\r\nCookie: session=7c1e9b2d04f5a3e8\r\n\r\n";
+
+    #[test]
+    fn test_recover_cookie() {
+        let connection = Aes128ChainedCBC::with_iv(vec![0x42; 16]);
+        let victim = |path: &[u8]| {
+            connection
+                .encrypt([HEAD, path, SECRET].concat(), KEY)
+                .unwrap()
+        };
+        let chosen = |plain_text: &[u8]| connection.encrypt(plain_text, KEY).unwrap();
+
+        let attack = ChainedIvAttack::new(victim, chosen, 16, HEAD.len());
+        assert_eq!(attack.recover_secret().unwrap(), SECRET);
+        assert!(attack.records() <= SECRET.len() * 257 + 17);
+    }
+
+    #[test]
+    fn test_random_iv_resists() {
+        // fresh IVs per record, the guesses never line up
+        let fresh = Cell::new(0u8);
+        let encrypt = |plain_text: &[u8]| {
+            fresh.set(fresh.get().wrapping_add(1));
+            Aes128CBC::with_iv(vec![fresh.get(); 16])
+                .encrypt(plain_text, KEY)
+                .unwrap()
+        };
+        let victim = |path: &[u8]| encrypt(&[HEAD, path, SECRET].concat());
+        let attack = ChainedIvAttack::new(victim, encrypt, 16, HEAD.len());
+        assert!(attack.recover_secret().is_err());
+    }
+}
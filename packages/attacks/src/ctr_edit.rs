@@ -0,0 +1,50 @@
+/// Recover the plaintext of `cipher_text` through an exposed CTR edit
+/// function `edit(cipher_text, offset, new_text)` (cryptopals challenge 25).
+///
+/// Editing in the ciphertext itself as new text xors it with the same
+/// keystream once more, which is decrypting it.
+pub fn recover_with_edit<F>(cipher_text: &[u8], edit: F) -> anyhow::Result<Vec<u8>>
+where
+    F: Fn(&[u8], usize, &[u8]) -> Vec<u8>,
+{
+    let replayed = edit(cipher_text, 0, cipher_text);
+    if replayed.len() != cipher_text.len() {
+        anyhow::bail!("ctr_edit: edit changed the cipher text length");
+    }
+    Ok(replayed)
+}
+
+/// Keystream under `cipher_text`, by editing in zeros.
+pub fn recover_keystream<F>(cipher_text: &[u8], edit: F) -> anyhow::Result<Vec<u8>>
+where
+    F: Fn(&[u8], usize, &[u8]) -> Vec<u8>,
+{
+    let keystream = edit(cipher_text, 0, &vec![0; cipher_text.len()]);
+    if keystream.len() != cipher_text.len() {
+        anyhow::bail!("ctr_edit: edit changed the cipher text length");
+    }
+    Ok(keystream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128Ctr, Modes};
+
+    const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+
+    #[test]
+    fn test_recover_with_edit() {
+        let ctr = Aes128Ctr::with_nonce(vec![4; 8]);
+        let plain_text = b"Now that the party is jumping, with the bass kicked in".to_vec();
+        let cipher_text = ctr.encrypt(&plain_text, KEY).unwrap();
+        let edit =
+            |c: &[u8], offset: usize, new_text: &[u8]| ctr.edit(c, KEY, offset, new_text).unwrap();
+
+        assert_eq!(recover_with_edit(&cipher_text, edit).unwrap(), plain_text);
+        assert_eq!(
+            recover_keystream(&cipher_text, edit).unwrap(),
+            ctr.encrypt(vec![0; plain_text.len()], KEY).unwrap()
+        );
+    }
+}
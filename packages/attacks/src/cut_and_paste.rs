@@ -0,0 +1,107 @@
+use aes::padding::{CipherPadding, PKCS7Padding};
+use blockanalysis::{analyze, BlockMode};
+
+// attacker input when only its length matters
+const FILLER: u8 = b'A';
+
+/// ECB cut-and-paste (cryptopals challenge 13).
+///
+/// Forges `Enc(prefix || input || suffix')` where `suffix'` is the oracle's
+/// suffix with its last `replaced_len` bytes swapped for `new_tail`, e.g.
+/// `email=..&uid=10&role=user` into `email=..&uid=10&role=admin`.
+/// Offsets come from measuring the oracle with [`blockanalysis::analyze`]:
+/// `PKCS7(new_tail)` is encrypted as whole blocks right after the prefix, then
+/// spliced after a ciphertext whose replaced bytes start a block.
+pub fn forge_tail<F: Fn(&[u8]) -> Vec<u8>>(
+    oracle: F,
+    replaced_len: usize,
+    new_tail: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    let structure = analyze(&oracle)?;
+    if structure.mode != BlockMode::Ecb {
+        anyhow::bail!("cut_and_paste: oracle is not using ECB mode");
+    }
+    if replaced_len > structure.suffix_len {
+        anyhow::bail!(
+            "cut_and_paste: can not replace {replaced_len} bytes of a {} bytes suffix",
+            structure.suffix_len
+        );
+    }
+    let bs = structure.block_size;
+
+    // Enc(PKCS7(new_tail)), block aligned by the prefix filler
+    let padded = pkcs7(new_tail, bs)?;
+    let input = [vec![FILLER; structure.prefix_pad()], padded.clone()].concat();
+    let start = structure.first_input_block() * bs;
+    let tail_blocks = oracle(&input)[start..start + padded.len()].to_vec();
+
+    // everything before the replaced bytes, padded to a block boundary
+    let head_len = structure.prefix_len + structure.suffix_len - replaced_len;
+    let fill = (bs - head_len % bs) % bs;
+    let cipher_text = oracle(&vec![FILLER; fill]);
+
+    Ok([&cipher_text[..head_len + fill], tail_blocks.as_slice()].concat())
+}
+
+//
+// Private Functions
+//
+
+// the padding is generic over the block size, dispatch on the measured one
+fn pkcs7(data: &[u8], block_size: usize) -> anyhow::Result<Vec<u8>> {
+    let data = data.to_vec();
+    Ok(match block_size {
+        8 => PKCS7Padding::add_pad::<8>(data),
+        16 => PKCS7Padding::add_pad::<16>(data),
+        32 => PKCS7Padding::add_pad::<32>(data),
+        _ => anyhow::bail!("cut_and_paste: unsupported block size {block_size}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128, Aes128CBC, Modes};
+    use kvcookie::{profile_for, Cookie};
+
+    const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+
+    #[test]
+    fn test_forge_admin_profile() {
+        let oracle = |email: &[u8]| {
+            let profile = profile_for(String::from_utf8(email.to_vec()).unwrap()).unwrap();
+            Aes128::new().encrypt(profile, KEY).unwrap()
+        };
+
+        let forged = forge_tail(oracle, "user".len(), b"admin").unwrap();
+        let profile = Aes128::new().decrypt(forged, KEY).unwrap();
+        let cookie = Cookie::parse(String::from_utf8(profile).unwrap()).unwrap();
+        assert_eq!(cookie.get("role"), Some("admin"));
+        assert_eq!(cookie.get("uid"), Some("10"));
+    }
+
+    #[test]
+    fn test_forge_any_layout() {
+        for prefix_len in [0, 3, 16, 20] {
+            for suffix in ["&role=user", ";x=1;role=guest;"] {
+                let prefix = vec![b'p'; prefix_len];
+                let oracle = |input: &[u8]| {
+                    Aes128::new()
+                        .encrypt([&prefix, input, suffix.as_bytes()].concat(), KEY)
+                        .unwrap()
+                };
+                let replaced_len = suffix.len() - suffix.find("role=").unwrap() - 5;
+                let forged = forge_tail(oracle, replaced_len, b"administrator").unwrap();
+                let plain = Aes128::new().decrypt(forged, KEY).unwrap();
+                assert!(plain.starts_with(&prefix));
+                assert!(plain.ends_with(b"role=administrator"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cbc_rejected() {
+        let oracle = |input: &[u8]| Aes128CBC::new().encrypt(input, KEY).unwrap();
+        assert!(forge_tail(oracle, 4, b"admin").is_err());
+    }
+}
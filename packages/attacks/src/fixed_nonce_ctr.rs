@@ -0,0 +1,163 @@
+use xor::guess_single_xor_key;
+
+/// Statistical attack on ciphertexts sharing one CTR keystream (cryptopals
+/// challenges 19 & 20).
+///
+/// Column `i` of every ciphertext is the plaintext column xored with the
+/// same keystream byte, so each column is a single byte xor cipher. Columns
+/// past the shortest ciphertext are solved from fewer samples and are less
+/// reliable, analyst pinned plaintext bytes always win over guesses.
+#[derive(Debug, Clone)]
+pub struct FixedNonceCtr {
+    cipher_texts: Vec<Vec<u8>>,
+    keystream: Vec<u8>,
+    // columns fixed by known plaintext, never guessed again
+    pinned: Vec<bool>,
+}
+
+impl FixedNonceCtr {
+    pub fn new<T: AsRef<[u8]>>(cipher_texts: &[T]) -> Self {
+        let cipher_texts = cipher_texts
+            .iter()
+            .map(|c| c.as_ref().to_vec())
+            .collect::<Vec<_>>();
+        let max_len = cipher_texts.iter().map(Vec::len).max().unwrap_or(0);
+        FixedNonceCtr {
+            cipher_texts,
+            keystream: vec![0; max_len],
+            pinned: vec![false; max_len],
+        }
+    }
+
+    // length of the shortest ciphertext, the columns with every sample
+    pub fn min_len(&self) -> usize {
+        self.cipher_texts.iter().map(Vec::len).min().unwrap_or(0)
+    }
+
+    /// Truncate every ciphertext to the shortest one and solve it as a
+    /// repeating key xor with key size `min_len`.
+    pub fn solve_truncated(&mut self) -> &[u8] {
+        let min_len = self.min_len();
+        self.solve_columns(0..min_len);
+        &self.keystream[..min_len]
+    }
+
+    /// Solve every column, including those past the shortest ciphertext
+    /// with only the ciphertexts which are long enough.
+    pub fn solve(&mut self) -> &[u8] {
+        self.solve_columns(0..self.keystream.len());
+        &self.keystream
+    }
+
+    /// Pin `known` as the plaintext of ciphertext `index` at `offset`, which
+    /// fixes those keystream bytes for every other ciphertext too.
+    pub fn pin(&mut self, index: usize, offset: usize, known: &[u8]) -> anyhow::Result<()> {
+        let cipher_text = self
+            .cipher_texts
+            .get(index)
+            .ok_or(anyhow::anyhow!("FixedNonceCtr: no cipher text {index}"))?;
+        if offset + known.len() > cipher_text.len() {
+            anyhow::bail!(
+                "FixedNonceCtr: known plaintext goes past cipher text {index} ({} bytes)",
+                cipher_text.len()
+            );
+        }
+
+        for (i, (c, p)) in cipher_text[offset..].iter().zip(known).enumerate() {
+            self.keystream[offset + i] = c ^ p;
+            self.pinned[offset + i] = true;
+        }
+        Ok(())
+    }
+
+    pub fn keystream(&self) -> &[u8] {
+        &self.keystream
+    }
+
+    // every ciphertext decrypted with the current keystream
+    pub fn plain_texts(&self) -> Vec<Vec<u8>> {
+        self.cipher_texts
+            .iter()
+            .map(|c| c.iter().zip(&self.keystream).map(|(a, b)| a ^ b).collect())
+            .collect()
+    }
+
+    fn solve_columns(&mut self, columns: std::ops::Range<usize>) {
+        for col in columns {
+            if self.pinned[col] {
+                continue;
+            }
+            let column = self
+                .cipher_texts
+                .iter()
+                .filter_map(|c| c.get(col).copied())
+                .collect::<Vec<_>>();
+            let (key, _, _) = guess_single_xor_key(&column, 0..=255);
+            self.keystream[col] = key as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128Ctr, Modes};
+
+    const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+    const LINES: [&str; 12] = [
+        "It was the best of times, it was the worst of times",
+        "it was the age of wisdom, it was the age of foolishness",
+        "it was the epoch of belief, it was the epoch of incredulity",
+        "it was the season of light, it was the season of darkness",
+        "it was the spring of hope, it was the winter of despair",
+        "we had everything before us, we had nothing before us",
+        "we were all going direct to heaven",
+        "we were all going direct the other way",
+        "in short, the period was so far like the present period",
+        "that some of its noisiest authorities insisted on its being received",
+        "for good or for evil, in the superlative degree of comparison only",
+        "there were a king with a large jaw and a queen with a plain face",
+    ];
+
+    fn encrypt_all() -> Vec<Vec<u8>> {
+        LINES
+            .iter()
+            .map(|l| Aes128Ctr::new().encrypt(l, KEY).unwrap())
+            .collect()
+    }
+
+    fn correct_bytes(recovered: &[u8], expected: &[u8]) -> usize {
+        recovered
+            .iter()
+            .zip(expected)
+            .filter(|(a, b)| a == b)
+            .count()
+    }
+
+    #[test]
+    fn test_solve_truncated() {
+        let mut attack = FixedNonceCtr::new(&encrypt_all());
+        let keystream = attack.solve_truncated().to_vec();
+        let expected = Aes128Ctr::new().encrypt([0; 34], KEY).unwrap();
+        assert_eq!(keystream.len(), 34);
+        // only 12 samples per column, a few of them are expected to be off
+        assert!(correct_bytes(&keystream, &expected) >= 28);
+    }
+
+    #[test]
+    fn test_pin_propagates() {
+        let cipher_texts = encrypt_all();
+        let mut attack = FixedNonceCtr::new(&cipher_texts);
+        attack.solve();
+
+        // knowing the longest line gives every other line in full
+        attack.pin(9, 0, LINES[9].as_bytes()).unwrap();
+        attack.solve();
+        for (plain, line) in attack.plain_texts().iter().zip(LINES) {
+            assert_eq!(plain, line.as_bytes());
+        }
+
+        assert!(attack.pin(6, 30, b"too long for line 6").is_err());
+        assert!(attack.pin(42, 0, b"x").is_err());
+    }
+}
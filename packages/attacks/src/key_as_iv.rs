@@ -0,0 +1,56 @@
+/// Recover the key of a CBC oracle using the key as IV (cryptopals challenge 27).
+///
+/// `decrypt(cipher_text)` is the receiver, which rejects non ASCII plaintext
+/// with an error revealing the plaintext (`Err(plain_text)`). Sending
+/// `C1 || 0 || C1 || C2..` gives `P'1 = InvCipher(C1) ^ K` and
+/// `P'3 = InvCipher(C1) ^ 0`, so `K = P'1 ^ P'3`. Replaying the whole
+/// original message after the two forged blocks keeps its padding valid.
+pub fn recover_key<F>(cipher_text: &[u8], block_size: usize, decrypt: F) -> anyhow::Result<Vec<u8>>
+where
+    F: Fn(&[u8]) -> Result<(), Vec<u8>>,
+{
+    let bs = block_size;
+    if cipher_text.len() < bs {
+        anyhow::bail!("key_as_iv: need at least one block of cipher text");
+    }
+
+    let forged = [&cipher_text[..bs], &vec![0; bs], cipher_text].concat();
+    let plain_text = match decrypt(&forged) {
+        Err(plain_text) if plain_text.len() >= 3 * bs => plain_text,
+        Err(_) => anyhow::bail!("key_as_iv: leaked plaintext is too short"),
+        Ok(()) => anyhow::bail!("key_as_iv: forged message was accepted, nothing leaked"),
+    };
+
+    Ok(plain_text[..bs]
+        .iter()
+        .zip(&plain_text[2 * bs..3 * bs])
+        .map(|(a, b)| a ^ b)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128CBC, Modes};
+
+    #[test]
+    fn test_recover_key() {
+        let key = b"YELLOW SUBMARINE";
+        let cbc = Aes128CBC::with_key_as_iv();
+        let cipher_text = cbc
+            .encrypt("comment1=cooking%20MCs;userdata=x;comment2=%20like", key)
+            .unwrap();
+        let decrypt = |c: &[u8]| {
+            let plain_text = cbc.decrypt(c, key).unwrap();
+            if plain_text.is_ascii() {
+                Ok(())
+            } else {
+                Err(plain_text)
+            }
+        };
+
+        assert_eq!(decrypt(&cipher_text), Ok(()));
+        assert_eq!(recover_key(&cipher_text, 16, decrypt).unwrap(), key);
+        assert!(recover_key(&cipher_text[..8], 16, decrypt).is_err());
+    }
+}
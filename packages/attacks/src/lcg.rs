@@ -0,0 +1,245 @@
+use prng::JavaRandom;
+
+// hidden low bits the bit-by-bit search branches on, above it a lattice
+// attack is needed
+const MAX_HIDDEN_BITS: u32 = 24;
+
+/// Recover a `java.util.Random` from consecutive `nextInt()` outputs.
+///
+/// Each output is the top 32 bits of the 48-bit state, the 16 hidden bits
+/// are brute forced and checked against the other outputs. Returns the
+/// generator positioned after the last output, [`JavaRandom::step_back`]
+/// leads back to the seed.
+pub fn recover_java_random(outputs: &[i32]) -> anyhow::Result<JavaRandom> {
+    if outputs.len() < 2 {
+        anyhow::bail!("lcg: 2 consecutive outputs at least are needed");
+    }
+    let high = (outputs[0] as u32 as u64) << 16;
+    (0..1 << 16)
+        .map(|low| JavaRandom::from_state(high | low))
+        .find_map(|mut rng| {
+            outputs[1..]
+                .iter()
+                .all(|&output| rng.next_int() == output)
+                .then_some(rng)
+        })
+        .ok_or(anyhow::anyhow!(
+            "lcg: outputs are not consecutive java.util.Random nextInt()"
+        ))
+}
+
+/// LCG `s' = a * s + c mod 2^m` whose outputs are bits `shift` to
+/// `shift + output_bits - 1` of each new state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncatedLcg {
+    pub multiplier: u64,
+    pub increment: u64,
+    pub modulus_bits: u32,
+    pub shift: u32,
+    pub output_bits: u32,
+}
+
+impl TruncatedLcg {
+    /// `java.util.Random::nextInt()`
+    pub const JAVA_NEXT_INT: Self = TruncatedLcg {
+        multiplier: 0x5_deec_e66d,
+        increment: 0xb,
+        modulus_bits: 48,
+        shift: 16,
+        output_bits: 32,
+    };
+
+    /// glibc TYPE_0 `rand()`, the whole state
+    pub const GLIBC_TYPE0: Self = TruncatedLcg {
+        multiplier: 1_103_515_245,
+        increment: 12_345,
+        modulus_bits: 31,
+        shift: 0,
+        output_bits: 31,
+    };
+
+    /// MSVC `rand()`
+    pub const MSVC: Self = TruncatedLcg {
+        multiplier: 214_013,
+        increment: 2_531_011,
+        modulus_bits: 32,
+        shift: 16,
+        output_bits: 15,
+    };
+
+    /// Only the low `bits` bits of each output are seen, e.g. `rand() % 256`.
+    pub fn low_bits(self, bits: u32) -> Self {
+        TruncatedLcg {
+            output_bits: bits.min(self.output_bits),
+            ..self
+        }
+    }
+
+    // bits of the state which affect the outputs, carries only go up
+    pub fn state_bits(&self) -> u32 {
+        self.shift + self.output_bits
+    }
+
+    /// Next state (modulo `2^state_bits`) and its output.
+    pub fn step(&self, state: u64) -> (u64, u64) {
+        let state = self.advance(state, self.state_bits());
+        (state, (state >> self.shift) & mask(self.output_bits))
+    }
+
+    /// States that produced the first of these consecutive outputs, modulo
+    /// `2^state_bits` (the higher bits never show up in the outputs).
+    ///
+    /// The low `j` bits of the state evolve on their own, so the state is
+    /// found bit by bit from the lowest. Hidden bits below `shift` can only
+    /// be branched on, every higher bit is checked against bit `j` of all
+    /// the outputs which prunes the wrong branches at once. Fails when too
+    /// many bits are hidden for this to be practical.
+    pub fn recover(&self, outputs: &[u64]) -> anyhow::Result<Vec<u64>> {
+        if self.shift > MAX_HIDDEN_BITS {
+            anyhow::bail!(
+                "lcg: {} hidden bits, at most {} can be searched",
+                self.shift,
+                MAX_HIDDEN_BITS
+            );
+        }
+        if self.state_bits() > self.modulus_bits {
+            anyhow::bail!("lcg: outputs beyond the modulus");
+        }
+        // nothing would be pruned, every state is a candidate
+        if outputs.is_empty() || self.output_bits == 0 {
+            anyhow::bail!("lcg: no output bits to check the states against");
+        }
+
+        let mut candidates = vec![0u64];
+        for j in 0..self.state_bits() {
+            candidates = candidates
+                .into_iter()
+                .flat_map(|c| [c, c | 1 << j])
+                .filter(|&c| j < self.shift || self.consistent(c, j, outputs))
+                .collect();
+        }
+        Ok(candidates)
+    }
+
+    // bit j of the states from `state` matches bit j - shift of the outputs
+    fn consistent(&self, state: u64, j: u32, outputs: &[u64]) -> bool {
+        let mut state = state;
+        outputs.iter().enumerate().all(|(i, output)| {
+            if i > 0 {
+                state = self.advance(state, j + 1);
+            }
+            (state >> j) & 1 == (output >> (j - self.shift)) & 1
+        })
+    }
+
+    // next state modulo 2^bits
+    fn advance(&self, state: u64, bits: u32) -> u64 {
+        state
+            .wrapping_mul(self.multiplier)
+            .wrapping_add(self.increment)
+            & mask(bits)
+    }
+}
+
+//
+// Private Functions
+//
+
+fn mask(bits: u32) -> u64 {
+    u64::MAX.checked_shr(64 - bits).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::{GlibcType0, MsvcRand};
+
+    #[test]
+    fn test_recover_java_random() {
+        let mut target = JavaRandom::new(0x1337_c0de);
+        let outputs = (0..3).map(|_| target.next_int()).collect::<Vec<_>>();
+
+        let mut cloned = recover_java_random(&outputs).unwrap();
+        assert_eq!(cloned, target);
+        assert!((0..100).all(|_| cloned.next_int() == target.next_int()));
+
+        // back to the seed
+        let mut seeded = recover_java_random(&outputs).unwrap();
+        (0..3).for_each(|_| seeded.step_back());
+        assert_eq!(seeded.seed(), 0x1337_c0de);
+
+        assert!(recover_java_random(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_nothing_to_check() {
+        assert!(TruncatedLcg::MSVC.recover(&[]).is_err());
+        let blind = TruncatedLcg::MSVC.low_bits(0);
+        assert_eq!(blind.step(1).1, 0);
+        assert!(blind.recover(&[0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_bit_by_bit_msvc() {
+        let mut target = MsvcRand::new(0xdead_beef);
+        let outputs = (0..6).map(|_| target.rand() as u64).collect::<Vec<_>>();
+
+        let lcg = TruncatedLcg::MSVC;
+        let states = lcg.recover(&outputs).unwrap();
+        assert_eq!(states.len(), 1);
+
+        // the 31 low bits, bit 31 never matters
+        let mut clone = MsvcRand::from_state(states[0] as u32);
+        outputs.iter().skip(1).for_each(|_| {
+            clone.rand();
+        });
+        assert!((0..100).all(|_| clone.rand() == target.rand()));
+    }
+
+    #[test]
+    fn test_bit_by_bit_glibc_mod_256() {
+        // rand() % 256, the low byte of the state is all that can be known
+        let mut target = GlibcType0::new(31337);
+        let outputs = (0..8)
+            .map(|_| (target.rand() % 256) as u64)
+            .collect::<Vec<_>>();
+
+        let lcg = TruncatedLcg::GLIBC_TYPE0.low_bits(8);
+        let states = lcg.recover(&outputs).unwrap();
+        assert_eq!(states.len(), 1);
+
+        let mut state = states[0];
+        (1..outputs.len()).for_each(|_| state = lcg.step(state).0);
+        for _ in 0..100 {
+            let (next, output) = lcg.step(state);
+            assert_eq!(output, (target.rand() % 256) as u64);
+            state = next;
+        }
+    }
+
+    #[test]
+    fn test_bit_by_bit_java() {
+        let mut target = JavaRandom::new(-99);
+        let outputs = (0..2)
+            .map(|_| target.next_int() as u32 as u64)
+            .collect::<Vec<_>>();
+        let states = TruncatedLcg::JAVA_NEXT_INT.recover(&outputs).unwrap();
+        assert_eq!(
+            states,
+            [recover_java_random(&[outputs[0] as i32, outputs[1] as i32])
+                .map(|mut r| {
+                    r.step_back();
+                    r.state()
+                })
+                .unwrap()]
+        );
+
+        // nextInt(16) leaks the top 4 bits only, 44 hidden bits
+        let top_bits = TruncatedLcg {
+            shift: 44,
+            output_bits: 4,
+            ..TruncatedLcg::JAVA_NEXT_INT
+        };
+        assert!(top_bits.recover(&outputs).is_err());
+    }
+}
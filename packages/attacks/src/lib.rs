@@ -0,0 +1,16 @@
+pub mod bitflip;
+pub mod byte_at_a_time;
+pub mod chained_iv;
+pub mod ctr_edit;
+pub mod cut_and_paste;
+pub mod fixed_nonce_ctr;
+pub mod key_as_iv;
+pub mod lcg;
+pub mod lucky13;
+pub mod mt_clone;
+pub mod mt_linear;
+pub mod mt_seed;
+pub mod mt_stream;
+pub mod padding_oracle;
+pub mod poodle;
+pub mod rc4_bias;
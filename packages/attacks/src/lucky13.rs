@@ -0,0 +1,149 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Response times of a server to one record.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub samples: Vec<Duration>,
+}
+
+impl Timings {
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        sorted.get(sorted.len() / 2).copied().unwrap_or_default()
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.samples.len() {
+            0 => Duration::ZERO,
+            n => self.samples.iter().sum::<Duration>() / n as u32,
+        }
+    }
+}
+
+/// Time `server` on each of `records`, `rounds` times.
+///
+/// Records are interleaved within each round so that noise and drift of the
+/// machine spread evenly over all of them.
+pub fn measure<F, R>(server: F, records: &[Vec<u8>], rounds: usize) -> Vec<Timings>
+where
+    F: Fn(&[u8]) -> R,
+{
+    let mut timings = vec![Timings::default(); records.len()];
+    for _ in 0..rounds {
+        for (record, timing) in records.iter().zip(timings.iter_mut()) {
+            let start = Instant::now();
+            black_box(server(black_box(record)));
+            timing.samples.push(start.elapsed());
+        }
+    }
+    timings
+}
+
+/// Lucky13 probe, `record` (`IV || C`) with `delta` xored into the end of
+/// the second last block, which flips the same bytes at the end of the
+/// plaintext (i.e. of the padding).
+///
+/// A guess of the last plaintext bytes is right when the flipped padding is
+/// valid and long, the MAC is then computed over fewer blocks and the server
+/// answers a little faster.
+pub fn probe(record: &[u8], block_size: usize, delta: &[u8]) -> anyhow::Result<Vec<u8>> {
+    if record.len() < 2 * block_size
+        || !record.len().is_multiple_of(block_size)
+        || delta.len() > block_size
+    {
+        anyhow::bail!("lucky13: record is not an IV and a ciphertext block at least");
+    }
+
+    let mut probe = record.to_vec();
+    let end = record.len() - block_size;
+    probe[end - delta.len()..end]
+        .iter_mut()
+        .zip(delta)
+        .for_each(|(c, d)| *c ^= d);
+    Ok(probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::SeededRng;
+    use std::cell::RefCell;
+    use toytls::{RecordLayer, Version};
+
+    #[test]
+    #[ignore = "wall clock timings, depends on machine load"]
+    fn test_padding_length_leaks() {
+        // stand-in server with the keys, only reachable through timing
+        let layer = RecordLayer::with_rng(
+            Version::Tls,
+            b"YELLOW SUBMARINE",
+            [0x5a; 32],
+            SeededRng::from_test_env(),
+        );
+        let server = |record: &[u8]| layer.open(record).is_ok();
+
+        // 255 bytes of padding skip 4 compression blocks of the MAC
+        let valid = layer.seal_with_padding([b'x'; 32], 255).unwrap();
+        // 0xff ^ 0x01, padding is invalid and the whole record is MACed
+        let invalid = probe(&valid, 16, &[0x01]).unwrap();
+        assert!(server(&valid[..]));
+        assert!(!server(&invalid[..]));
+
+        // a majority of repeated measurements, one round can be skewed by a
+        // busy machine
+        let rounds = 5;
+        let faster = (0..rounds)
+            .filter(|_| {
+                let timings = measure(server, &[valid.clone(), invalid.clone()], 2000);
+                assert!(timings[0].mean() > Duration::ZERO);
+                timings[0].median() < timings[1].median()
+            })
+            .count();
+        assert!(faster * 2 > rounds, "valid faster in {faster} / {rounds}");
+    }
+
+    #[test]
+    fn test_measure() {
+        // known cost, the first byte of a record is the spin count in units
+        // far above the timer resolution, calls are logged to check the
+        // interleaving
+        let calls = RefCell::new(vec![]);
+        let server = |record: &[u8]| {
+            calls.borrow_mut().push(record[1]);
+            (0..record[0] as u64 * 100_000).fold(0u64, |a, i| black_box(a ^ i))
+        };
+        let records = [vec![20, 0], vec![0, 1], vec![5, 2]];
+
+        let timings = measure(server, &records, 9);
+        assert!(timings.iter().all(|t| t.samples.len() == 9));
+        assert_eq!(calls.into_inner(), [0, 1, 2].repeat(9));
+        assert!(timings[1].median() < timings[2].median());
+        assert!(timings[2].median() < timings[0].median());
+        assert!(timings[1].mean() < timings[0].mean());
+    }
+
+    #[test]
+    fn test_timings() {
+        let ms = Duration::from_millis;
+        let timings = Timings {
+            samples: vec![ms(9), ms(1), ms(2)],
+        };
+        assert_eq!(timings.median(), ms(2));
+        assert_eq!(timings.mean(), ms(4));
+        assert_eq!(Timings::default().median(), Duration::ZERO);
+        assert_eq!(Timings::default().mean(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_probe() {
+        let record = vec![0; 48];
+        let probe = probe(&record, 16, &[1, 2]).unwrap();
+        assert_eq!(&probe[30..32], &[1, 2]);
+        assert!(probe[..30].iter().chain(&probe[32..]).all(|&b| b == 0));
+        assert!(super::probe(&record[..20], 16, &[1]).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use prng::{untemper, Mt19937, Rng, STATE_LEN};
+
+/// MT19937 rebuilt from its outputs.
+#[derive(Debug, Clone)]
+pub struct ClonedMt {
+    /// Positioned right after the last output it was given.
+    pub rng: Mt19937,
+    /// Outputs past the first 624 which were checked against the clone.
+    pub checked: usize,
+    /// Index (in the given outputs) of the first one the clone got wrong.
+    pub first_mismatch: Option<usize>,
+}
+
+impl ClonedMt {
+    pub fn is_consistent(&self) -> bool {
+        self.first_mismatch.is_none()
+    }
+}
+
+/// Clone an MT19937 from at least 624 consecutive 32-bit outputs
+/// (cryptopals challenge 23).
+///
+/// Untempering an output gives back a word of state. The twist computes each
+/// new word from words 624, 623 and 227 places before it, whatever its place
+/// in the state array, so any 624 consecutive outputs make a valid state
+/// even when they don't start at a twist. Outputs after the first 624 are
+/// predicted by the clone and compared.
+pub fn clone_mt19937(outputs: &[u32]) -> anyhow::Result<ClonedMt> {
+    if outputs.len() < STATE_LEN {
+        anyhow::bail!(
+            "mt_clone: {} outputs given, {} are needed",
+            outputs.len(),
+            STATE_LEN
+        );
+    }
+
+    let mut state = [0; STATE_LEN];
+    state
+        .iter_mut()
+        .zip(outputs)
+        .for_each(|(word, &output)| *word = untemper(output));
+
+    // the next call twists, just like after 624 outputs of a fresh seed
+    let mut rng = Mt19937::from_state(state, STATE_LEN);
+    let extra = &outputs[STATE_LEN..];
+    let mut first_mismatch = None;
+    for (i, &output) in extra.iter().enumerate() {
+        if rng.next_u32() != output && first_mismatch.is_none() {
+            first_mismatch = Some(STATE_LEN + i);
+        }
+    }
+
+    Ok(ClonedMt {
+        rng,
+        checked: extra.len(),
+        first_mismatch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputs(rng: &mut Mt19937, n: usize) -> Vec<u32> {
+        (0..n).map(|_| rng.next_u32()).collect()
+    }
+
+    #[test]
+    fn test_clone_at_twist() {
+        let mut mt = Mt19937::new(0x1234);
+        let cloned = clone_mt19937(&outputs(&mut mt, STATE_LEN)).unwrap();
+        assert_eq!(cloned.checked, 0);
+        let mut clone = cloned.rng;
+        assert!((0..5000).all(|_| clone.next_u32() == mt.next_u32()));
+    }
+
+    #[test]
+    fn test_clone_between_twists() {
+        for skip in [1, 100, 623, 625, 1000] {
+            let mut mt = Mt19937::new(skip as u32);
+            outputs(&mut mt, skip);
+            let cloned = clone_mt19937(&outputs(&mut mt, STATE_LEN + 50)).unwrap();
+            assert!(cloned.is_consistent());
+            assert_eq!(cloned.checked, 50);
+
+            let mut clone = cloned.rng;
+            assert_eq!(outputs(&mut clone, 2000), outputs(&mut mt, 2000));
+        }
+    }
+
+    #[test]
+    fn test_inconsistent_samples() {
+        let mut mt = Mt19937::new(1);
+        let mut samples = outputs(&mut mt, STATE_LEN + 10);
+        // a gap, the outputs are not consecutive anymore
+        samples.remove(STATE_LEN + 3);
+        let cloned = clone_mt19937(&samples).unwrap();
+        assert_eq!(cloned.first_mismatch, Some(STATE_LEN + 3));
+
+        assert!(clone_mt19937(&samples[..STATE_LEN - 1]).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use gf2::{BitVec, Equation, LinearSystem};
+use prng::{Mt19937, Rng, STATE_LEN};
+
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_b0df;
+
+/// Unknown bits of an MT19937 state, the top bit of the oldest word and
+/// the 623 other words, the low 31 bits of the oldest word are never used.
+pub const STATE_BITS: usize = 1 + (STATE_LEN - 1) * 32;
+
+// each bit of a word as a combination of the unknown state bits
+type SymbolicWord = Vec<BitVec>;
+
+/// Recovers an MT19937 state from partially leaked outputs.
+///
+/// Twisting and tempering are both linear over GF(2), so every output bit is
+/// a known combination of the 19937 unknown state bits. The solver runs the
+/// generator symbolically, each leaked bit adds an equation and the state is
+/// solved by Gaussian elimination once the system reaches full rank.
+///
+/// Leaks are bit masks of each output, e.g. `0xff00_0000` for the top 8
+/// bits or `0xff` for `rand() % 256`. A zero mask skips an output.
+pub struct MtSolver {
+    // last 624 symbolic state words, in the generator's own order
+    window: Vec<SymbolicWord>,
+    index: usize,
+    system: LinearSystem,
+    outputs: usize,
+}
+
+impl MtSolver {
+    pub fn new() -> Self {
+        let window = (0..STATE_LEN)
+            .map(|j| {
+                (0..32)
+                    .map(|b| match (j, b) {
+                        (0, 31) => BitVec::unit(STATE_BITS, 0),
+                        (0, _) => BitVec::zeros(STATE_BITS),
+                        _ => BitVec::unit(STATE_BITS, variable(j, b)),
+                    })
+                    .collect()
+            })
+            .collect();
+        MtSolver {
+            window,
+            index: 0,
+            system: LinearSystem::new(STATE_BITS),
+            outputs: 0,
+        }
+    }
+
+    // outputs observed (or skipped) so far
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    // independent leaked bits so far, solved at `STATE_BITS`
+    pub fn rank(&self) -> usize {
+        self.system.rank()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.system.is_full_rank()
+    }
+
+    /// Next output of the generator, of which only the bits in `mask` are
+    /// known. Returns `true` if it raised the rank.
+    pub fn observe(&mut self, mask: u32, value: u32) -> bool {
+        let output = self.next_symbolic();
+        self.outputs += 1;
+        (0..32)
+            .filter(|b| mask >> b & 1 == 1)
+            .fold(false, |raised, b| {
+                let equation = Equation::new(output[b].clone(), value >> b & 1 == 1);
+                self.system.add(equation) | raised
+            })
+    }
+
+    pub fn skip(&mut self) {
+        self.observe(0, 0);
+    }
+
+    /// Generator positioned right after the observed outputs.
+    ///
+    /// Fails until the system is full rank, or if the leaks contradict each
+    /// other (they did not come from consecutive MT19937 outputs).
+    pub fn solve(&self) -> anyhow::Result<Mt19937> {
+        if !self.is_solved() {
+            anyhow::bail!(
+                "mt_linear: rank {} of {}, more outputs are needed",
+                self.rank(),
+                STATE_BITS
+            );
+        }
+        let x = self
+            .system
+            .solve()
+            .ok_or(anyhow::anyhow!("mt_linear: leaked bits are inconsistent"))?;
+
+        let mut state = [0u32; STATE_LEN];
+        state[0] = (x.get(0) as u32) << 31;
+        for (j, word) in state.iter_mut().enumerate().skip(1) {
+            *word = (0..32).fold(0, |w, b| w | (x.get(variable(j, b)) as u32) << b);
+        }
+        let mut rng = Mt19937::from_state(state, STATE_LEN);
+        (0..self.outputs).for_each(|_| {
+            rng.next_u32();
+        });
+        Ok(rng)
+    }
+
+    // twist one word in place and temper it, as `Mt19937::next_u32`
+    fn next_symbolic(&mut self) -> SymbolicWord {
+        let i = self.index;
+        let (upper, lower) = (&self.window[i], &self.window[(i + 1) % STATE_LEN]);
+        // y = upper bit of word i, lower 31 bits of word i + 1
+        let y = (0..32)
+            .map(|b| if b == 31 { &upper[b] } else { &lower[b] })
+            .collect::<Vec<_>>();
+
+        let mut word = self.window[(i + M) % STATE_LEN].clone();
+        for (b, bit) in word.iter_mut().enumerate() {
+            // y >> 1
+            if b < 31 {
+                *bit ^= y[b + 1];
+            }
+            // MATRIX_A when the low bit of y is set
+            if MATRIX_A >> b & 1 == 1 {
+                *bit ^= y[0];
+            }
+        }
+        self.window[i] = word.clone();
+        self.index = (i + 1) % STATE_LEN;
+
+        let word = xor_shifted(word, 11, u32::MAX, false);
+        let word = xor_shifted(word, 7, 0x9d2c_5680, true);
+        let word = xor_shifted(word, 15, 0xefc6_0000, true);
+        xor_shifted(word, 18, u32::MAX, false)
+    }
+}
+
+impl Default for MtSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outputs needed to recover the state when each one leaks the bits of
+/// `mask`, found by adding (value-less) equations until full rank. `None`
+/// if `limit` outputs are not enough.
+pub fn outputs_needed(mask: u32, limit: usize) -> Option<usize> {
+    let mut solver = MtSolver::new();
+    while solver.outputs() < limit {
+        solver.observe(mask, 0);
+        if solver.is_solved() {
+            return Some(solver.outputs());
+        }
+    }
+    None
+}
+
+//
+// Private Functions
+//
+
+fn variable(word: usize, bit: usize) -> usize {
+    1 + (word - 1) * 32 + bit
+}
+
+// y ^ ((y << shift) & mask) or y ^ ((y >> shift) & mask)
+fn xor_shifted(y: SymbolicWord, shift: usize, mask: u32, left: bool) -> SymbolicWord {
+    (0..32usize)
+        .map(|b| {
+            let source = match left {
+                true => b.checked_sub(shift),
+                false => Some(b + shift).filter(|&s| s < 32),
+            };
+            match source {
+                Some(s) if mask >> b & 1 == 1 => &y[b] ^ &y[s],
+                _ => y[b].clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recover_from_top_bits() {
+        let mut mt = Mt19937::new(0x5eed);
+        (0..1000).for_each(|_| {
+            mt.next_u32();
+        });
+
+        // only the top byte of each output, e.g. a random byte generator
+        let mut solver = MtSolver::new();
+        while !solver.is_solved() {
+            assert!(solver.outputs() < 3000);
+            solver.observe(0xff00_0000, mt.next_u32() & 0xff00_0000);
+        }
+        // 19937 / 8 at least
+        assert!(solver.outputs() >= STATE_BITS / 8);
+
+        let mut clone = solver.solve().unwrap();
+        assert!((0..2000).all(|_| clone.next_u32() == mt.next_u32()));
+    }
+
+    #[test]
+    fn test_recover_from_low_bits_with_gaps() {
+        // rand() % 2^16, and every third output is not seen
+        let mut mt = Mt19937::new(42);
+        let mut solver = MtSolver::new();
+        while !solver.is_solved() {
+            let output = mt.next_u32();
+            match solver.outputs() % 3 {
+                2 => solver.skip(),
+                _ => {
+                    solver.observe(0xffff, output & 0xffff);
+                }
+            }
+        }
+        let mut clone = solver.solve().unwrap();
+        assert!((0..2000).all(|_| clone.next_u32() == mt.next_u32()));
+    }
+
+    #[test]
+    fn test_outputs_needed() {
+        let needed = outputs_needed(0xff00_0000, 3000).unwrap();
+        // every leaked bit is independent, 2493 outputs
+        assert_eq!(needed, STATE_BITS.div_ceil(8));
+        assert_eq!(outputs_needed(0xff00_0000, 100), None);
+
+        let mut solver = MtSolver::new();
+        solver.observe(u32::MAX, 0);
+        assert!(solver.solve().is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    thread,
+};
+
+use prng::Mt19937;
+
+// seeds handed to a thread at a time
+const CHUNK: u64 = 1 << 14;
+
+/// Search `seeds` for one accepted by `matches`, split across `threads`.
+///
+/// Threads take chunks of seeds in increasing order and all stop at the
+/// first match, if several seeds match any of them may be returned.
+pub fn search_seeds<F>(seeds: RangeInclusive<u32>, threads: usize, matches: F) -> Option<u32>
+where
+    F: Fn(u32) -> bool + Sync,
+{
+    let (start, end) = (*seeds.start() as u64, *seeds.end() as u64);
+    let next = AtomicU64::new(start);
+    let found = AtomicBool::new(false);
+
+    let worker = || {
+        while !found.load(Ordering::Relaxed) {
+            let from = next.fetch_add(CHUNK, Ordering::Relaxed);
+            if from > end {
+                break;
+            }
+            let to = (from + CHUNK - 1).min(end);
+            if let Some(seed) = (from..=to).map(|s| s as u32).find(|&s| matches(s)) {
+                found.store(true, Ordering::Relaxed);
+                return Some(seed);
+            }
+        }
+        None
+    };
+
+    thread::scope(|scope| {
+        let handles = (0..threads.max(1))
+            .map(|_| scope.spawn(worker))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().expect("mt_seed: search thread panicked"))
+            .next()
+    })
+}
+
+/// Recover the Unix timestamp an MT19937 was seeded with from its first
+/// output, searching the `window` of seconds before it was observed
+/// (cryptopals challenge 22).
+pub fn recover_timestamp_seed(
+    first_output: u32,
+    window: RangeInclusive<u64>,
+    threads: usize,
+) -> Option<u32> {
+    // seeds are 32-bit, good until 2106
+    let start = u32::try_from(*window.start()).ok()?;
+    let end = u32::try_from(*window.end()).unwrap_or(u32::MAX);
+    search_seeds(start..=end, threads, |seed| {
+        Mt19937::first_output(seed) == first_output
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::{Clock, Rng, VirtualClock};
+
+    fn threads() -> usize {
+        thread::available_parallelism().map_or(4, |n| n.get())
+    }
+
+    #[test]
+    fn test_search_millions() {
+        // 2^20 seeds, 12 days of timestamps
+        let seed = 1_700_000_000 + (1 << 20) - 5;
+        let first = Mt19937::new(seed).next_u32();
+        let window = 1_700_000_000..=1_700_000_000 + (1 << 20);
+        assert_eq!(recover_timestamp_seed(first, window, threads()), Some(seed));
+    }
+
+    #[test]
+    fn test_timestamp_seed() {
+        let clock = VirtualClock::new(1_760_000_000);
+        let service = clock.clone();
+
+        clock.advance(523);
+        let mut rng = Mt19937::new(service.now() as u32);
+        clock.advance(777);
+        let first = rng.next_u32();
+
+        let now = clock.now();
+        let seed = recover_timestamp_seed(first, now - 3600..=now, 2);
+        assert_eq!(seed, Some(1_760_000_523));
+        assert_eq!(recover_timestamp_seed(first, now - 10..=now, 2), None);
+    }
+
+    #[test]
+    fn test_search_edges() {
+        assert_eq!(
+            search_seeds(u32::MAX - 3..=u32::MAX, 3, |s| s == u32::MAX),
+            Some(u32::MAX)
+        );
+        assert_eq!(search_seeds(5..=5, 0, |s| s == 5), Some(5));
+        assert_eq!(search_seeds(0..=100, 2, |_| false), None);
+    }
+}
@@ -0,0 +1,88 @@
+use prng::{Clock, Mt19937, Mt19937Cipher, Rng};
+
+use crate::mt_seed::search_seeds;
+
+/// Recover the 16-bit key of an [`Mt19937Cipher`] ciphertext whose last
+/// bytes are known, e.g. `Enc(random prefix || A^14)` (cryptopals
+/// challenge 24). Every seed is tried on the known suffix only.
+pub fn recover_stream_key(
+    cipher_text: &[u8],
+    known_suffix: &[u8],
+    threads: usize,
+) -> anyhow::Result<u16> {
+    if known_suffix.is_empty() || known_suffix.len() > cipher_text.len() {
+        anyhow::bail!("mt_stream: known suffix must be within the ciphertext");
+    }
+    let offset = cipher_text.len() - known_suffix.len();
+    let tail = &cipher_text[offset..];
+
+    let cipher = Mt19937Cipher::new();
+    search_seeds(0..=u16::MAX as u32, threads, |seed| {
+        let key = (seed as u16).to_le_bytes();
+        cipher
+            .apply_keystream_at(tail, key, offset)
+            .is_ok_and(|plain| plain == known_suffix)
+    })
+    .map(|seed| seed as u16)
+    .ok_or(anyhow::anyhow!(
+        "mt_stream: no 16-bit seed matches the suffix"
+    ))
+}
+
+/// Seed of `token` if it is the first output bytes of an MT19937 seeded
+/// with the time, within `window` seconds before `clock.now()`.
+///
+/// Only the first 4 bytes are needed to find a candidate, the rest of the
+/// token confirms it.
+pub fn detect_time_seeded_token<C: Clock>(
+    token: &[u8],
+    clock: &C,
+    window: u64,
+    threads: usize,
+) -> Option<u32> {
+    let first = u32::from_le_bytes(token.get(..4)?.try_into().ok()?);
+    let now = u32::try_from(clock.now()).ok()?;
+    let start = now.saturating_sub(window.min(u32::MAX as u64) as u32);
+
+    search_seeds(start..=now, threads, |seed| {
+        Mt19937::first_output(seed) == first
+            && Mt19937::new(seed).random_bytes(token.len()) == token
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::VirtualClock;
+
+    #[test]
+    fn test_recover_stream_key() {
+        let cipher = Mt19937Cipher::new();
+        let key = 0xc0de_u16.to_le_bytes();
+        let plain_text = [&b"random-ish prefix"[..], &[b'A'; 14]].concat();
+        let cipher_text = cipher.encrypt(&plain_text, key).unwrap();
+
+        let recovered = recover_stream_key(&cipher_text, &[b'A'; 14], 2).unwrap();
+        assert_eq!(recovered, 0xc0de);
+        assert!(recover_stream_key(&cipher_text, &[], 2).is_err());
+    }
+
+    #[test]
+    fn test_detect_token() {
+        let clock = VirtualClock::new(1_760_000_000);
+        let token = Mt19937::new(clock.now() as u32).random_bytes(16);
+        clock.advance(90);
+        assert_eq!(
+            detect_time_seeded_token(&token, &clock, 300, 2),
+            Some(1_760_000_000)
+        );
+
+        // too long ago
+        clock.advance(1000);
+        assert_eq!(detect_time_seeded_token(&token, &clock, 300, 2), None);
+        // first 4 bytes match but not the rest
+        let mut forged = Mt19937::new(clock.now() as u32).random_bytes(16);
+        forged[10] ^= 1;
+        assert_eq!(detect_time_seeded_token(&forged, &clock, 300, 2), None);
+    }
+}
@@ -0,0 +1,157 @@
+use std::cell::Cell;
+
+// attacker controlled path & body bytes
+const FILLER: u8 = b'A';
+// each try succeeds with probability 1/256
+const MAX_TRIES_PER_BYTE: usize = 256 * 16;
+
+/// POODLE, decrypting SSLv3 records through the unchecked CBC padding.
+///
+/// `victim(path, body)` makes the victim send a record `IV || C` of
+/// `head || path || secret || body` (e.g. a request with the path in the
+/// url, a cookie header and a body), with a fresh IV each time. Only the
+/// length of `head` is known. `server(record)` forwards a record to the
+/// server and tells if it was accepted.
+///
+/// With a whole block of padding only its last byte `B - 1` is checked.
+/// Replacing the padding block with a block `C[i]` of the secret is accepted
+/// when `D(C[i])` ends in `B - 1 ^ C[n - 1][B - 1]`, once in 256 tries on
+/// average, which reveals the last byte of plaintext block `i`.
+pub struct Poodle<V, S> {
+    victim: V,
+    server: S,
+    block_size: usize,
+    head_len: usize,
+    queries: Cell<usize>,
+}
+
+impl<V, S> Poodle<V, S>
+where
+    V: Fn(&[u8], &[u8]) -> Vec<u8>,
+    S: Fn(&[u8]) -> bool,
+{
+    pub fn new(victim: V, server: S, block_size: usize, head_len: usize) -> Self {
+        Poodle {
+            victim,
+            server,
+            block_size,
+            head_len,
+            queries: Cell::new(0),
+        }
+    }
+
+    // number of records sent to the server
+    pub fn queries(&self) -> usize {
+        self.queries.get()
+    }
+
+    /// Recover the first `len` bytes following the path.
+    pub fn recover(&self, len: usize) -> anyhow::Result<Vec<u8>> {
+        let bs = self.block_size;
+        let full_pad = self.full_padding_body()?;
+
+        let mut known = Vec::with_capacity(len);
+        while known.len() < len {
+            // shift the next byte to the end of a block, shrink the body to
+            // keep the padding a whole block
+            let path_len = bs - 1 - (self.head_len + known.len()) % bs;
+            let body_len = (full_pad + bs - path_len) % bs;
+            let i = (self.head_len + path_len + known.len()) / bs;
+
+            let byte = (0..MAX_TRIES_PER_BYTE)
+                .find_map(|_| {
+                    let record = (self.victim)(&vec![FILLER; path_len], &vec![FILLER; body_len]);
+                    // blocks shifted by one for the IV
+                    let blocks = record.chunks_exact(bs).collect::<Vec<_>>();
+                    let n = blocks.len();
+                    let tampered = [&blocks[..n - 1], &[blocks[i + 1]]].concat().concat();
+
+                    self.queries.set(self.queries.get() + 1);
+                    (self.server)(&tampered)
+                        .then(|| (bs - 1) as u8 ^ blocks[n - 2][bs - 1] ^ blocks[i][bs - 1])
+                })
+                .ok_or(anyhow::anyhow!(
+                    "poodle: no record accepted for byte {}, padding is checked",
+                    known.len()
+                ))?;
+            known.push(byte);
+        }
+        Ok(known)
+    }
+
+    // body length with which the padding is a whole block (with an empty path)
+    fn full_padding_body(&self) -> anyhow::Result<usize> {
+        let base_len = (self.victim)(&[], &[]).len();
+        for i in 1..=self.block_size {
+            if (self.victim)(&[], &vec![FILLER; i]).len() > base_len {
+                return Ok(i);
+            }
+        }
+        anyhow::bail!("poodle: record length never changed, not a padded block mode")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+    use prng::SeededRng;
+    use toytls::{Endpoint, RecordLayer, Version};
+
+    const ENC_KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+    const MAC_KEY: &[u8; 32] = b"MAC KEY MAC KEY MAC KEY MAC KEY!";
+    const HEAD: &[u8] = b"GET /";
+    const SECRET: &[u8] = b" HTTP/1.1\r\nCookie: session=7c1e9b2d\r\n\r\n";
+
+    // client -> attacker -> server, the attacker reads and rewrites records
+    fn mitm(version: Version) -> anyhow::Result<Vec<u8>> {
+        let (to_attacker, from_client) = channel();
+        let (to_server, from_attacker) = channel();
+        let (unused_tx, unused_rx) = channel();
+        // the server only opens records, sharing the stream is harmless
+        let rng = SeededRng::from_test_env();
+        let client = Endpoint::new(
+            RecordLayer::with_rng(version, ENC_KEY, MAC_KEY, rng.clone()),
+            to_attacker,
+            unused_rx,
+        );
+        let server = Endpoint::new(
+            RecordLayer::with_rng(version, ENC_KEY, MAC_KEY, rng),
+            unused_tx,
+            from_attacker,
+        );
+
+        let victim = |path: &[u8], body: &[u8]| {
+            client.send([HEAD, path, SECRET, body].concat()).unwrap();
+            from_client.recv().unwrap()
+        };
+        let forward = |record: &[u8]| {
+            to_server.send(record.to_vec()).unwrap();
+            server.recv().is_ok()
+        };
+
+        let attack = Poodle::new(victim, forward, 16, HEAD.len());
+        let secret = attack.recover(SECRET.len())?;
+        // 256 tries per byte on average
+        assert!(attack.queries() < SECRET.len() * 256 * 3);
+        Ok(secret)
+    }
+
+    #[test]
+    fn test_poodle_ssl3() {
+        assert_eq!(mitm(Version::Ssl3).unwrap(), SECRET);
+    }
+
+    #[test]
+    fn test_tls_padding_resists() {
+        // the body of the full padding block is checked
+        let layer =
+            RecordLayer::with_rng(Version::Tls, ENC_KEY, MAC_KEY, SeededRng::from_test_env());
+        let victim =
+            |path: &[u8], body: &[u8]| layer.seal([HEAD, path, SECRET, body].concat()).unwrap();
+        let server = |record: &[u8]| layer.open(record).is_ok();
+        let attack = Poodle::new(victim, server, 16, HEAD.len());
+        assert!(attack.recover(1).is_err());
+    }
+}
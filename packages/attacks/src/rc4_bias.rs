@@ -0,0 +1,297 @@
+use std::{
+    collections::BTreeSet,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use prng::SeededRng;
+
+// samples handed to a thread at a time
+const CHUNK: u64 = 1 << 12;
+
+/// Single byte bias of the RC4 keystream, `Z[index]` is `value` more often
+/// than `1/256` (0-based, `Z2` is `Z[1]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bias {
+    pub index: usize,
+    pub value: u8,
+}
+
+impl Bias {
+    /// Mantin-Shamir, `Pr[Z2 = 0] ~ 2/256`.
+    pub const Z2: Bias = Bias { index: 1, value: 0 };
+    /// AlFardan et al., `Pr[Z16 = 240] ~ 1/256 (1 + 2^-4.8)`.
+    pub const Z16: Bias = Bias {
+        index: 15,
+        value: 240,
+    };
+    /// AlFardan et al., `Pr[Z32 = 224] ~ 1/256 (1 + 2^-5.1)`.
+    pub const Z32: Bias = Bias {
+        index: 31,
+        value: 224,
+    };
+}
+
+/// Sampling progress, reported after every chunk of encryptions.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    // request prefix length currently sampled
+    pub prefix_len: usize,
+    // encryptions done so far, out of `total` for the whole recovery
+    pub samples: u64,
+    pub total: u64,
+}
+
+/// Recover the first `len` bytes of a secret from an RC4 oracle encrypting
+/// `prefix || secret || ..` under a fresh key per call (cryptopals
+/// challenge 56).
+///
+/// Each prefix length `L` puts secret byte `p = index - L` under the biased
+/// keystream bytes, `samples` ciphertexts are collected per prefix length
+/// and for every byte the candidate `c` maximizing the summed counts of
+/// `c ^ bias.value` over the usable biases is picked. Bytes not reachable
+/// by any bias are an error. With `Z16` & `Z32` recovery needs around 2^24
+/// samples per prefix length, spread over `threads`.
+///
+/// The oracle draws its fresh keys from the generator it is handed, seeded
+/// from `seed` and the prefix length (see [`sample_counts`]), so a run is
+/// replayed from its seed whatever the number of threads.
+pub fn recover_secret<F, P>(
+    oracle: F,
+    len: usize,
+    biases: &[Bias],
+    samples: u64,
+    threads: usize,
+    seed: u64,
+    progress: P,
+) -> anyhow::Result<Vec<u8>>
+where
+    F: Fn(&[u8], &mut SeededRng) -> Vec<u8> + Sync,
+    P: Fn(Progress) + Sync,
+{
+    if let Some(p) = (0..len).find(|&p| biases.iter().all(|b| b.index < p)) {
+        anyhow::bail!("rc4_bias: secret byte {} is past every bias", p);
+    }
+
+    let prefix_lens = (0..len)
+        .flat_map(|p| biases.iter().filter_map(move |b| b.index.checked_sub(p)))
+        .collect::<BTreeSet<_>>();
+    let total = samples * prefix_lens.len() as u64;
+
+    // scores[p][c], summed counts of c ^ bias.value over all biases
+    let mut scores = vec![[0u64; 256]; len];
+    for (round, &prefix_len) in prefix_lens.iter().enumerate() {
+        // only the biases landing inside the secret, the ciphertext may
+        // not reach the others
+        let active = biases
+            .iter()
+            .filter(|b| (prefix_len..prefix_len + len).contains(&b.index))
+            .copied()
+            .collect::<Vec<_>>();
+        let done = round as u64 * samples;
+        let round_seed = seed ^ ((prefix_len as u64) << 32);
+        let counts = sample_counts(
+            &oracle,
+            prefix_len,
+            &active,
+            samples,
+            threads,
+            round_seed,
+            |n| {
+                progress(Progress {
+                    prefix_len,
+                    samples: done + n,
+                    total,
+                })
+            },
+        )?;
+
+        for (bias, counts) in active.iter().zip(&counts) {
+            let p = bias.index - prefix_len;
+            for c in 0..256 {
+                scores[p][c] += counts[c ^ bias.value as usize];
+            }
+        }
+    }
+
+    Ok(scores
+        .iter()
+        .map(|s| (0..=255u8).max_by_key(|&c| s[c as usize]).unwrap())
+        .collect())
+}
+
+/// Histograms of the ciphertext bytes at each bias index, over `samples`
+/// encryptions with a `prefix_len` bytes prefix, split across `threads`.
+/// `progress` gets the number of samples done.
+///
+/// Samples are handed out in chunks, chunk `i` calls the oracle with
+/// `SeededRng::new(seed ^ i)`, the counts only depend on `seed` and not on
+/// which thread took which chunk.
+pub fn sample_counts<F, P>(
+    oracle: &F,
+    prefix_len: usize,
+    biases: &[Bias],
+    samples: u64,
+    threads: usize,
+    seed: u64,
+    progress: P,
+) -> anyhow::Result<Vec<[u64; 256]>>
+where
+    F: Fn(&[u8], &mut SeededRng) -> Vec<u8> + Sync,
+    P: Fn(u64) + Sync,
+{
+    let prefix = vec![b'A'; prefix_len];
+    let next = AtomicU64::new(0);
+    let done = AtomicU64::new(0);
+
+    let worker = || -> anyhow::Result<Vec<[u64; 256]>> {
+        let mut counts = vec![[0u64; 256]; biases.len()];
+        loop {
+            let from = next.fetch_add(CHUNK, Ordering::Relaxed);
+            if from >= samples {
+                return Ok(counts);
+            }
+            let to = (from + CHUNK).min(samples);
+            let mut rng = SeededRng::new(seed ^ from / CHUNK);
+            for _ in from..to {
+                let cipher_text = oracle(&prefix, &mut rng);
+                for (bias, counts) in biases.iter().zip(counts.iter_mut()) {
+                    let byte = cipher_text.get(bias.index).ok_or(anyhow::anyhow!(
+                        "rc4_bias: ciphertext of {} bytes is shorter than the bias index {}",
+                        cipher_text.len(),
+                        bias.index
+                    ))?;
+                    counts[*byte as usize] += 1;
+                }
+            }
+            progress(done.fetch_add(to - from, Ordering::Relaxed) + to - from);
+        }
+    };
+
+    thread::scope(|scope| {
+        let handles = (0..threads.max(1))
+            .map(|_| scope.spawn(worker))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .try_fold(vec![[0u64; 256]; biases.len()], |mut total, h| {
+                let counts = h.join().expect("rc4_bias: sampling thread panicked")?;
+                for (t, c) in total.iter_mut().zip(counts) {
+                    t.iter_mut().zip(c).for_each(|(t, c)| *t += c);
+                }
+                Ok(total)
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::Rng;
+    use rc4::Rc4;
+    use std::sync::Mutex;
+
+    const SECRET: &[u8] = b"QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F";
+
+    fn oracle(prefix: &[u8], rng: &mut SeededRng) -> Vec<u8> {
+        let key = rng.random_bytes(16);
+        Rc4::new().encrypt([prefix, SECRET].concat(), key).unwrap()
+    }
+
+    // uniform keystream except Z16 & Z32, biased 1/16 of the time instead
+    // of ~1/256 (1 + 2^-5), the real biases need far more samples than a
+    // debug build gets through in a test
+    fn amplified_oracle(prefix: &[u8], rng: &mut SeededRng) -> Vec<u8> {
+        let mut keystream = rng.random_bytes(prefix.len() + SECRET.len());
+        for bias in [Bias::Z16, Bias::Z32] {
+            if rng.gen_range(0..16) == 0 {
+                keystream[bias.index] = bias.value;
+            }
+        }
+        [prefix, SECRET]
+            .concat()
+            .iter()
+            .zip(keystream)
+            .map(|(p, k)| p ^ k)
+            .collect()
+    }
+
+    fn seed() -> u64 {
+        SeededRng::from_test_env().seed()
+    }
+
+    #[test]
+    fn test_z2_recovers_two_bytes() {
+        // Z2 alone only reaches the first 2 bytes, with a strong enough
+        // bias for 2^15 samples
+        let reports = Mutex::new(vec![]);
+        let recovered = recover_secret(oracle, 2, &[Bias::Z2], 1 << 15, 2, seed(), |p| {
+            reports.lock().unwrap().push(p)
+        })
+        .unwrap();
+        assert_eq!(recovered, &SECRET[..2]);
+
+        let reports = reports.into_inner().unwrap();
+        let last = reports.iter().max_by_key(|p| p.samples).unwrap();
+        assert_eq!((last.samples, last.total), (1 << 16, 1 << 16));
+        assert!(reports.iter().all(|p| p.prefix_len <= 1));
+    }
+
+    #[test]
+    fn test_z16_z32_pick_the_right_byte() {
+        // bytes 0..16 are under both biases, 16..32 only under Z32
+        let biases = [Bias::Z16, Bias::Z32];
+        let recovered =
+            recover_secret(amplified_oracle, 32, &biases, 1 << 10, 2, seed(), |_| ()).unwrap();
+        assert_eq!(recovered, &SECRET[..32]);
+
+        // a single bias at a time, the other one would point elsewhere
+        let counts =
+            sample_counts(&amplified_oracle, 0, &biases, 1 << 10, 2, seed(), |_| ()).unwrap();
+        for (bias, counts) in biases.iter().zip(&counts) {
+            let top = (0..=255u8).max_by_key(|&b| counts[b as usize]).unwrap();
+            assert_eq!(top ^ bias.value, SECRET[bias.index]);
+        }
+    }
+
+    #[test]
+    fn test_replay_any_threads() {
+        let seed = seed();
+        let counts = |threads| {
+            sample_counts(
+                &oracle,
+                3,
+                &[Bias::Z16],
+                5 * CHUNK + 7,
+                threads,
+                seed,
+                |_| (),
+            )
+            .unwrap()
+        };
+        let single = counts(1);
+        assert_eq!(counts(3), single);
+        assert_eq!(single[0].iter().sum::<u64>(), 5 * CHUNK + 7);
+    }
+
+    #[test]
+    #[ignore = "2^24 RC4 encryptions per prefix length, run with --release"]
+    fn test_z16_z32_recover_secret() {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let biases = [Bias::Z16, Bias::Z32];
+        let recovered =
+            recover_secret(oracle, 32, &biases, 1 << 24, threads, seed(), |_| ()).unwrap();
+        assert_eq!(recovered, &SECRET[..32]);
+    }
+
+    #[test]
+    fn test_unreachable_byte() {
+        assert!(recover_secret(oracle, 3, &[Bias::Z2], 1, 1, 0, |_| ()).is_err());
+    }
+
+    #[test]
+    fn test_short_ciphertext() {
+        let short = |_: &[u8], _: &mut SeededRng| vec![0; 8];
+        assert!(sample_counts(&short, 0, &[Bias::Z16], 16, 2, 0, |_| ()).is_err());
+    }
+}
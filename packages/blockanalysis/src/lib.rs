@@ -0,0 +1,250 @@
+use std::{collections::HashMap, hash::Hash};
+
+// largest block size probed while watching for ciphertext length jumps
+const MAX_BLOCK_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockMode {
+    Ecb,
+    Cbc,
+}
+
+/// Layout of a chosen-plaintext oracle computing `Enc(prefix || input || suffix)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockStructure {
+    pub block_size: usize,
+    pub mode: BlockMode,
+    pub prefix_len: usize,
+    pub suffix_len: usize,
+}
+
+impl BlockStructure {
+    // number of filler bytes needed to push attacker input to a block boundary
+    pub fn prefix_pad(&self) -> usize {
+        (self.block_size - self.prefix_len % self.block_size) % self.block_size
+    }
+
+    // index of the first block fully controlled by attacker input,
+    // given `prefix_pad` filler bytes were sent first
+    pub fn first_input_block(&self) -> usize {
+        (self.prefix_len + self.prefix_pad()) / self.block_size
+    }
+}
+
+// counts of every chunk which occurs more than once, in descending order
+pub fn find_same_chunks<U, T>(data: T, chunk_size: usize) -> Vec<usize>
+where
+    U: Eq + Hash,
+    T: AsRef<[U]>,
+{
+    let mut map = HashMap::new();
+    for chunk in data.as_ref().chunks_exact(chunk_size) {
+        *map.entry(chunk).or_insert(0) += 1usize
+    }
+
+    let mut values = map.into_values().filter(|&v| v != 1).collect::<Vec<_>>();
+    values.sort_by(|a, b| b.cmp(a));
+    values
+}
+
+// average repetition of the repeated blocks, 0 if all blocks are unique
+pub fn score_ecb<T: AsRef<[u8]>>(cipher_text: T, block_size: usize) -> usize {
+    let same = find_same_chunks(cipher_text, block_size);
+    if same.is_empty() {
+        0
+    } else {
+        same.iter().sum::<usize>() / same.len()
+    }
+}
+
+// ECB maps equal plaintext blocks to equal ciphertext blocks, CBC doesn't.
+pub fn detect_mode<T: AsRef<[u8]>>(cipher_text: T, block_size: usize) -> BlockMode {
+    if find_same_chunks(cipher_text, block_size).is_empty() {
+        BlockMode::Cbc
+    } else {
+        BlockMode::Ecb
+    }
+}
+
+// 3 blocks of equal bytes always contain 2 aligned equal blocks, whatever the prefix.
+pub fn detect_oracle_mode<F>(oracle: F, block_size: usize) -> BlockMode
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    detect_mode(oracle(&vec![b'A'; 3 * block_size]), block_size)
+}
+
+pub fn detect_block_size<F>(oracle: F) -> anyhow::Result<usize>
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    Ok(length_jump(oracle)?.0)
+}
+
+/// Length of the fixed prefix the oracle puts before the input.
+///
+/// Sends `X^k || 0` and `X^k || 1` and watches in which block they first
+/// differ; the smallest `k` which moves that block forward reveals the prefix
+/// length. Works for any deterministic block mode (ECB, CBC with fixed IV).
+pub fn detect_prefix_len<F>(oracle: F, block_size: usize) -> anyhow::Result<usize>
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    let first_diff = |k: usize| -> anyhow::Result<usize> {
+        let mut input = vec![b'A'; k + 1];
+        let a = oracle(&input);
+        input[k] = b'B';
+        let b = oracle(&input);
+        a.chunks(block_size)
+            .zip(b.chunks(block_size))
+            .position(|(x, y)| x != y)
+            .ok_or(anyhow::anyhow!(
+                "detect_prefix_len: oracle output does not depend on input"
+            ))
+    };
+
+    let d0 = first_diff(0)?;
+    for k in 1..=block_size {
+        if first_diff(k)? > d0 {
+            return Ok((d0 + 1) * block_size - k);
+        }
+    }
+    anyhow::bail!("detect_prefix_len: oracle is not deterministic")
+}
+
+pub fn analyze<F>(oracle: F) -> anyhow::Result<BlockStructure>
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    let (block_size, jump_at, base_len) = length_jump(&oracle)?;
+    let mode = detect_oracle_mode(&oracle, block_size);
+    let prefix_len = detect_prefix_len(&oracle, block_size)?;
+
+    // with `jump_at` input bytes, prefix + input + suffix fills `base_len` exactly
+    let suffix_len = (base_len - jump_at)
+        .checked_sub(prefix_len)
+        .ok_or(anyhow::anyhow!(
+            "analyze: inconsistent prefix length ({prefix_len})"
+        ))?;
+
+    Ok(BlockStructure {
+        block_size,
+        mode,
+        prefix_len,
+        suffix_len,
+    })
+}
+
+//
+// Private Functions
+//
+
+// returns (block size, input length at the first jump, output length before the jump)
+fn length_jump<F>(oracle: F) -> anyhow::Result<(usize, usize, usize)>
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    let base_len = oracle(&[]).len();
+    for i in 1..=MAX_BLOCK_SIZE {
+        let len = oracle(&vec![b'A'; i]).len();
+        if len != base_len {
+            let jump = len.checked_sub(base_len).ok_or(anyhow::anyhow!(
+                "detect_block_size: oracle output got shorter ({base_len} to {len} bytes), not length-monotonic"
+            ))?;
+            return Ok((jump, i, base_len));
+        }
+    }
+    anyhow::bail!("detect_block_size: ciphertext length never changed, not a padded block mode")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::{Aes128, Aes128CBC, Modes};
+
+    const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+
+    fn ecb_oracle(prefix: Vec<u8>, suffix: Vec<u8>) -> impl Fn(&[u8]) -> Vec<u8> {
+        move |input| {
+            Aes128::new()
+                .encrypt([prefix.as_slice(), input, &suffix].concat(), KEY)
+                .unwrap()
+        }
+    }
+
+    fn cbc_oracle(prefix: Vec<u8>, suffix: Vec<u8>) -> impl Fn(&[u8]) -> Vec<u8> {
+        move |input| {
+            Aes128CBC::with_iv(vec![9; 16])
+                .encrypt([prefix.as_slice(), input, &suffix].concat(), KEY)
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_detect_mode() {
+        let plain = [b'A'; 64];
+        let ecb = Aes128::new().encrypt(plain, KEY).unwrap();
+        let cbc = Aes128CBC::new().encrypt(plain, KEY).unwrap();
+        assert_eq!(detect_mode(&ecb, 16), BlockMode::Ecb);
+        assert_eq!(detect_mode(&cbc, 16), BlockMode::Cbc);
+        assert_eq!(score_ecb(&ecb, 16), 4);
+        assert_eq!(score_ecb(&cbc, 16), 0);
+    }
+
+    #[test]
+    fn test_analyze_ecb() {
+        for prefix_len in [0, 1, 5, 15, 16, 17, 31, 40] {
+            for suffix_len in [0, 7, 16, 20] {
+                // prefix ending in the filler byte must not confuse the analysis
+                let structure =
+                    analyze(ecb_oracle(vec![b'A'; prefix_len], vec![b's'; suffix_len])).unwrap();
+                assert_eq!(
+                    structure,
+                    BlockStructure {
+                        block_size: 16,
+                        mode: BlockMode::Ecb,
+                        prefix_len,
+                        suffix_len,
+                    }
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_analyze_cbc() {
+        for prefix_len in [0, 3, 16, 21] {
+            let structure =
+                analyze(cbc_oracle(vec![b'p'; prefix_len], b"suffix".to_vec())).unwrap();
+            assert_eq!(structure.block_size, 16);
+            assert_eq!(structure.mode, BlockMode::Cbc);
+            assert_eq!(structure.prefix_len, prefix_len);
+            assert_eq!(structure.suffix_len, 6);
+        }
+    }
+
+    #[test]
+    fn test_prefix_pad() {
+        let structure = analyze(ecb_oracle(vec![0; 21], vec![])).unwrap();
+        assert_eq!(structure.prefix_pad(), 11);
+        assert_eq!(structure.first_input_block(), 2);
+
+        let structure = analyze(ecb_oracle(vec![0; 32], vec![])).unwrap();
+        assert_eq!(structure.prefix_pad(), 0);
+        assert_eq!(structure.first_input_block(), 2);
+    }
+
+    #[test]
+    fn test_not_a_block_cipher() {
+        // e.g. a fixed length token, whatever the input
+        let fixed = |_: &[u8]| vec![0u8; 32];
+        assert!(detect_block_size(fixed).is_err());
+    }
+
+    #[test]
+    fn test_shrinking_output() {
+        // e.g. a random length prefix, drawn again on every call
+        let shrinking = |input: &[u8]| vec![0u8; 64 - 16 * input.len().min(1)];
+        assert!(detect_block_size(shrinking).is_err());
+    }
+}
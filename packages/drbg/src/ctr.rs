@@ -0,0 +1,413 @@
+use aes::{
+    cipher::{AesCipher, BlockCipher},
+    secret::Secret,
+};
+
+use crate::{Drbg, DrbgResult, Entropy, Error, OsEntropy, MAX_REQUEST_LEN, RESEED_INTERVAL};
+
+const BLOCK: usize = 16;
+
+/// CTR_DRBG over AES with a `K` bit key (SP 800-90A 10.2).
+///
+/// With the derivation function inputs of any length are compressed with
+/// `Block_Cipher_df`, the entropy input is the security strength (`K / 8`
+/// bytes) plus a nonce. Without it the entropy input must be full entropy
+/// of the seed length and other inputs are only zero padded.
+pub struct CtrDrbg<const K: usize, E: Entropy = OsEntropy> {
+    key: Secret<Vec<u8>>,
+    v: Secret<[u8; BLOCK]>,
+    reseed_counter: u64,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+    derivation: bool,
+    entropy: E,
+}
+
+impl<const K: usize> CtrDrbg<K, OsEntropy> {
+    /// Instantiated from [`OsEntropy`] with the derivation function and a
+    /// random nonce.
+    pub fn new(personalization: &[u8]) -> DrbgResult<Self> {
+        let mut nonce = vec![0; K / 16];
+        OsEntropy.fill(&mut nonce)?;
+        Self::with_entropy(OsEntropy, &nonce, personalization, true)
+    }
+}
+
+impl<const K: usize, E: Entropy> CtrDrbg<K, E> {
+    const KEY_LEN: usize = K / 8;
+    const SEED_LEN: usize = K / 8 + BLOCK;
+
+    /// Instantiate with entropy from `entropy`. The nonce is only used with
+    /// the derivation function.
+    pub fn with_entropy(
+        mut entropy: E,
+        nonce: &[u8],
+        personalization: &[u8],
+        derivation: bool,
+    ) -> DrbgResult<Self> {
+        let mut entropy_input = Secret::new(vec![0; Self::entropy_len(derivation)]);
+        entropy.fill(&mut entropy_input)?;
+
+        let mut drbg = CtrDrbg {
+            key: Secret::new(vec![0; Self::KEY_LEN]),
+            v: Secret::new([0; BLOCK]),
+            reseed_counter: 1,
+            reseed_interval: RESEED_INTERVAL,
+            prediction_resistance: false,
+            derivation,
+            entropy,
+        };
+        let seed = drbg.seed_material(&[&entropy_input, nonce, personalization])?;
+        drbg.update(&seed);
+        Ok(drbg)
+    }
+
+    pub fn with_prediction_resistance(mut self) -> Self {
+        self.prediction_resistance = true;
+        self
+    }
+
+    pub fn with_reseed_interval(mut self, interval: u64) -> Self {
+        self.reseed_interval = interval.min(RESEED_INTERVAL);
+        self
+    }
+
+    fn entropy_len(derivation: bool) -> usize {
+        match derivation {
+            true => Self::KEY_LEN,
+            false => Self::SEED_LEN,
+        }
+    }
+
+    // seed length input, the derivation function of all the inputs or the
+    // full entropy input xored with the other input zero padded
+    fn seed_material(&self, inputs: &[&[u8]]) -> DrbgResult<Secret<Vec<u8>>> {
+        if self.derivation {
+            return Ok(block_cipher_df::<K>(&inputs.concat(), Self::SEED_LEN));
+        }
+
+        let (first, rest) = match inputs {
+            [entropy, _, rest] | [entropy, rest] => (*entropy, *rest),
+            _ => (inputs[0], &[][..]),
+        };
+        if rest.len() > Self::SEED_LEN {
+            return Err(Error::InputTooLong(rest.len(), Self::SEED_LEN));
+        }
+        let mut seed = Secret::new(vec![0; Self::SEED_LEN]);
+        seed[..first.len()].copy_from_slice(first);
+        seed.iter_mut().zip(rest).for_each(|(s, r)| *s ^= r);
+        Ok(seed)
+    }
+
+    // CTR_DRBG_Update
+    fn update(&mut self, provided: &[u8]) {
+        let mut temp = Secret::new(Vec::with_capacity(Self::SEED_LEN + BLOCK));
+        while temp.len() < Self::SEED_LEN {
+            increment(&mut self.v);
+            temp.extend(AesCipher::<K>::cipher(*self.v, &*self.key));
+        }
+        temp.truncate(Self::SEED_LEN);
+        temp.iter_mut().zip(provided).for_each(|(t, p)| *t ^= p);
+
+        self.key = Secret::new(temp[..Self::KEY_LEN].to_vec());
+        self.v.copy_from_slice(&temp[Self::KEY_LEN..]);
+    }
+}
+
+impl<const K: usize, E: Entropy> Drbg for CtrDrbg<K, E> {
+    fn reseed(&mut self, additional: &[u8]) -> DrbgResult<()> {
+        let mut entropy_input = Secret::new(vec![0; Self::entropy_len(self.derivation)]);
+        self.entropy.fill(&mut entropy_input)?;
+        let seed = self.seed_material(&[&entropy_input, additional])?;
+        self.update(&seed);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    fn generate(&mut self, n: usize, additional: &[u8]) -> DrbgResult<Vec<u8>> {
+        if n > MAX_REQUEST_LEN {
+            return Err(Error::RequestTooLarge(n));
+        }
+
+        let mut additional = additional;
+        if self.prediction_resistance || self.reseed_counter > self.reseed_interval {
+            self.reseed(additional)?;
+            additional = &[];
+        }
+
+        let additional = match additional.is_empty() {
+            true => Secret::new(vec![0; Self::SEED_LEN]),
+            false => {
+                let additional = self.seed_material(&[&[], additional])?;
+                self.update(&additional);
+                additional
+            }
+        };
+
+        let mut output = Vec::with_capacity(n + BLOCK);
+        while output.len() < n {
+            increment(&mut self.v);
+            output.extend(AesCipher::<K>::cipher(*self.v, &*self.key));
+        }
+        output.truncate(n);
+
+        self.update(&additional);
+        self.reseed_counter += 1;
+        Ok(output)
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+}
+
+/// Generated output as a [`prng::Rng`], panics if a reseed can not get
+/// entropy.
+impl<const K: usize, E: Entropy> prng::Rng for CtrDrbg<K, E> {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0; 4];
+        prng::Rng::fill_bytes(self, &mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        Drbg::fill_bytes(self, buf).expect("CtrDrbg: entropy source failed");
+    }
+}
+
+//
+// Private Functions
+//
+
+// V = (V + 1) mod 2^128
+fn increment(v: &mut [u8; BLOCK]) {
+    *v = u128::from_be_bytes(*v).wrapping_add(1).to_be_bytes();
+}
+
+// Block_Cipher_df (SP 800-90A 10.3.2), `n` bytes out of `input`
+fn block_cipher_df<const K: usize>(input: &[u8], n: usize) -> Secret<Vec<u8>> {
+    let key_len = K / 8;
+    // S = L || N || input || 0x80, zero padded to a whole block
+    let mut s = Secret::new(
+        [
+            &(input.len() as u32).to_be_bytes()[..],
+            &(n as u32).to_be_bytes(),
+            input,
+            &[0x80],
+        ]
+        .concat(),
+    );
+    let padded_len = s.len().div_ceil(BLOCK) * BLOCK;
+    s.resize(padded_len, 0);
+
+    let key = (0..key_len as u8).collect::<Vec<_>>();
+    let mut temp = Secret::new(vec![]);
+    for i in 0u32.. {
+        if temp.len() >= key_len + BLOCK {
+            break;
+        }
+        // BCC, CBC-MAC of IV || S with a zero IV
+        let mut iv = [0; BLOCK];
+        iv[..4].copy_from_slice(&i.to_be_bytes());
+        let chained =
+            [&iv[..], &s]
+                .concat()
+                .chunks_exact(BLOCK)
+                .fold([0; BLOCK], |chain, block| {
+                    let mut x = chain;
+                    x.iter_mut().zip(block).for_each(|(c, b)| *c ^= b);
+                    AesCipher::<K>::cipher(x, &key)
+                });
+        temp.extend(chained);
+    }
+
+    let key = Secret::new(temp[..key_len].to_vec());
+    let mut x = [0; BLOCK];
+    x.copy_from_slice(&temp[key_len..key_len + BLOCK]);
+    let mut output = Secret::new(Vec::with_capacity(n + BLOCK));
+    while output.len() < n {
+        x = AesCipher::<K>::cipher(x, &*key);
+        output.extend(x);
+    }
+    output.truncate(n);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::Fixed, Aes128CtrDrbg, Aes256CtrDrbg};
+
+    fn unhex(h: &str) -> Vec<u8> {
+        hex::decode(h).unwrap().0
+    }
+
+    // CAVP drbgvectors_no_reseed, CTR_DRBG.txt, COUNT = 0, the output of the
+    // second generate call is compared
+    #[test]
+    fn test_cavp_aes128_no_df() {
+        let entropy =
+            Fixed::new(&["ce50f33da5d4c1d3d4004eb35244b7f2cd7f2e5076fbf6780a7ff634b249a5fc"]);
+        let mut drbg = Aes128CtrDrbg::with_entropy(entropy, &[], &[], false).unwrap();
+        drbg.generate(64, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(64, &[]).unwrap(),
+            unhex(
+                "6545c0529d372443b392ceb3ae3a99a30f963eaf313280f1d1a1e87f9db373d3\
+                 61e75d18018266499cccd64d9bbb8de0185f213383080faddec46bae1f784e5a"
+            )
+        );
+        assert_eq!(drbg.reseed_counter(), 3);
+    }
+
+    #[test]
+    fn test_cavp_aes128_df() {
+        let entropy = Fixed::new(&["890eb067acf7382eff80b0c73bc872c6"]);
+        let nonce = unhex("aad471ef3ef1d203");
+        let mut drbg = Aes128CtrDrbg::with_entropy(entropy, &nonce, &[], true).unwrap();
+        drbg.generate(64, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(64, &[]).unwrap(),
+            unhex(
+                "a5514ed7095f64f3d0d3a5760394ab42062f373a25072a6ea6bcfd8489e94af6\
+                 cf18659fea22ed1ca0a9e33f718b115ee536b12809c31b72b08ddd8be1910fa3"
+            )
+        );
+    }
+
+    // CAVP drbgvectors_pr_true, CTR_DRBG.rsp [AES-128 use df], COUNT = 0,
+    // both generate calls reseed from EntropyInputPR first
+    #[test]
+    fn test_cavp_aes128_df_prediction_resistance() {
+        let entropy = Fixed::new(&[
+            "5d4041942bcf68864a4997d8171f1f9f",
+            "ef55a769b7eaf03fe082029bb32a2b9d",
+            "8239e865c0a42e14b964b9c09de85a20",
+        ]);
+        let nonce = unhex("d4f1f4ae08bcb3e1");
+        let mut drbg = Aes128CtrDrbg::with_entropy(entropy, &nonce, &[], true)
+            .unwrap()
+            .with_prediction_resistance();
+        drbg.generate(64, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(64, &[]).unwrap(),
+            unhex(
+                "4155320287eedcf7d484c2c2a1e2eb64b9c9ce77c87202a1ae1616c7a5cfd1c6\
+                 87c7a0bfcc85bda48fdd4629fd330c22d0a76076f88fc7cd04037ee06b7af602"
+            )
+        );
+        // every request reseeds, the source is now empty
+        assert!(drbg.generate(64, &[]).is_err());
+    }
+
+    // same file, [AES-256 use df], COUNT = 0
+    #[test]
+    fn test_cavp_aes256_df_prediction_resistance() {
+        let entropy = Fixed::new(&[
+            "16a1f035388cd8d956026e3b0117cb524dd3eb563f9a7720bb7dcb0fc6fbe743",
+            "cf140bcd4d7130e7e3ea14046c56442b57c43b34ad219553e7105c18f6e561af",
+            "e27c9f0be60d82d6cc474efb7fc737b16a6895d9a3a45b971d19b743c1a4ac8f",
+        ]);
+        let nonce = unhex("a2d015f22d854e29de278d910c573de5");
+        let mut drbg = Aes256CtrDrbg::with_entropy(entropy, &nonce, &[], true)
+            .unwrap()
+            .with_prediction_resistance();
+        drbg.generate(64, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(64, &[]).unwrap(),
+            unhex(
+                "b4e8395bcb7503410a94633f70e9904a5b30e62c35bc6dd2a03496c4a49932e1\
+                 84fbffdbcf1de1c72c50d36dc2ae8f04f40f96aae159c3fb816ca16df99b6c3e"
+            )
+        );
+    }
+
+    // CAVP drbgvectors_pr_false, CTR_DRBG.rsp [AES-128 use df], COUNT = 0,
+    // reseeded with EntropyInputReseed before the generate calls
+    #[test]
+    fn test_cavp_aes128_df_reseed() {
+        let entropy = Fixed::new(&[
+            "0f65da13dca407999d4773c2b4a11d85",
+            "1dea0a12c52bf64339dd291c80d8ca89",
+        ]);
+        let nonce = unhex("5209e5b4ed82a234");
+        let mut drbg = Aes128CtrDrbg::with_entropy(entropy, &nonce, &[], true).unwrap();
+        drbg.reseed(&[]).unwrap();
+        assert_eq!(drbg.reseed_counter(), 1);
+        drbg.generate(64, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(64, &[]).unwrap(),
+            unhex(
+                "2859cc468a76b08661ffd23b28547ffd0997ad526a0f51261b99ed3a37bd407b\
+                 f418dbe6c6c3e26ed0ddefcb7474d899bd99f3655427519fc5b4057bcaf306d4"
+            )
+        );
+    }
+
+    #[test]
+    fn test_reseed_interval() {
+        let inputs = [
+            "000102030405060708090a0b0c0d0e0f",
+            "303132333435363738393a3b3c3d3e3f",
+        ];
+        let mut drbg = Aes128CtrDrbg::with_entropy(Fixed::new(&inputs), b"nonce", &[], true)
+            .unwrap()
+            .with_reseed_interval(2);
+        drbg.generate(16, &[]).unwrap();
+        drbg.generate(16, &[]).unwrap();
+        assert_eq!(drbg.reseed_counter(), 3);
+
+        // reseeds with the additional input before the third request, as
+        // an explicit reseed would
+        let mut manual =
+            Aes128CtrDrbg::with_entropy(Fixed::new(&inputs), b"nonce", &[], true).unwrap();
+        manual.generate(16, &[]).unwrap();
+        manual.generate(16, &[]).unwrap();
+        manual.reseed(b"additional").unwrap();
+        assert_eq!(
+            drbg.generate(16, b"additional").unwrap(),
+            manual.generate(16, &[]).unwrap()
+        );
+        assert_eq!(drbg.reseed_counter(), 2);
+    }
+
+    #[test]
+    fn test_limits() {
+        let entropy = Fixed::new(&[&"00".repeat(32)]);
+        let mut drbg = Aes128CtrDrbg::with_entropy(entropy, &[], &[], false).unwrap();
+        assert!(matches!(
+            drbg.generate(MAX_REQUEST_LEN + 1, &[]),
+            Err(Error::RequestTooLarge(_))
+        ));
+        assert!(matches!(
+            drbg.generate(16, &[0; 33]),
+            Err(Error::InputTooLong(33, 32))
+        ));
+        assert_eq!(
+            drbg.generate(MAX_REQUEST_LEN, &[]).unwrap().len(),
+            MAX_REQUEST_LEN
+        );
+    }
+
+    #[test]
+    fn test_os_entropy() {
+        let mut a = Aes128CtrDrbg::new(b"test").unwrap();
+        let mut b = Aes256CtrDrbg::new(b"test").unwrap();
+        let mut buf = [0; 100];
+        a.fill_bytes(&mut buf).unwrap();
+        assert_ne!(buf, [0; 100]);
+        assert_ne!(a.generate(32, &[]).unwrap(), b.generate(32, &[]).unwrap());
+    }
+
+    #[test]
+    fn test_rng() {
+        let entropy = || Fixed::new(&[&"11".repeat(32)]);
+        let mut a = Aes128CtrDrbg::with_entropy(entropy(), &[], &[], false).unwrap();
+        let mut b = Aes128CtrDrbg::with_entropy(entropy(), &[], &[], false).unwrap();
+        assert_eq!(
+            prng::Rng::random_bytes(&mut a, 100),
+            b.generate(100, &[]).unwrap()
+        );
+        assert!(prng::Rng::gen_range(&mut a, 0..10) < 10);
+    }
+}
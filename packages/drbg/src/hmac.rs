@@ -0,0 +1,250 @@
+use aes::secret::Secret;
+use hash::{Hash, HmacSha256, Sha256};
+
+use crate::{Drbg, DrbgResult, Entropy, Error, OsEntropy, MAX_REQUEST_LEN, RESEED_INTERVAL};
+
+// security strength of SHA-256, entropy input length
+const ENTROPY_LEN: usize = 32;
+const NONCE_LEN: usize = 16;
+
+/// HMAC_DRBG over HMAC-SHA256 (SP 800-90A 10.1.2).
+pub struct HmacDrbg<E: Entropy = OsEntropy> {
+    key: Secret<Vec<u8>>,
+    v: Secret<Vec<u8>>,
+    reseed_counter: u64,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+    entropy: E,
+}
+
+impl HmacDrbg<OsEntropy> {
+    /// Instantiated from [`OsEntropy`], with a random nonce.
+    pub fn new(personalization: &[u8]) -> DrbgResult<Self> {
+        let mut nonce = [0; NONCE_LEN];
+        OsEntropy.fill(&mut nonce)?;
+        Self::with_entropy(OsEntropy, &nonce, personalization)
+    }
+}
+
+impl<E: Entropy> HmacDrbg<E> {
+    pub fn with_entropy(mut entropy: E, nonce: &[u8], personalization: &[u8]) -> DrbgResult<Self> {
+        let mut entropy_input = Secret::new(vec![0; ENTROPY_LEN]);
+        entropy.fill(&mut entropy_input)?;
+
+        let mut drbg = HmacDrbg {
+            key: Secret::new(vec![0; Sha256::OUTPUT_LEN]),
+            v: Secret::new(vec![1; Sha256::OUTPUT_LEN]),
+            reseed_counter: 1,
+            reseed_interval: RESEED_INTERVAL,
+            prediction_resistance: false,
+            entropy,
+        };
+        drbg.update(&[&entropy_input, nonce, personalization]);
+        Ok(drbg)
+    }
+
+    pub fn with_prediction_resistance(mut self) -> Self {
+        self.prediction_resistance = true;
+        self
+    }
+
+    pub fn with_reseed_interval(mut self, interval: u64) -> Self {
+        self.reseed_interval = interval.min(RESEED_INTERVAL);
+        self
+    }
+
+    // HMAC_DRBG_Update, the provided data is the concatenation of `provided`
+    fn update(&mut self, provided: &[&[u8]]) {
+        let empty = provided.iter().all(|p| p.is_empty());
+        for round in [0u8, 1] {
+            if round == 1 && empty {
+                break;
+            }
+            let mut mac = HmacSha256::new(&*self.key);
+            mac.update(&*self.v);
+            mac.update([round]);
+            provided.iter().for_each(|p| mac.update(p));
+            self.key = Secret::new(mac.finalize());
+            self.v = Secret::new(HmacSha256::mac(&*self.key, &*self.v));
+        }
+    }
+}
+
+impl<E: Entropy> Drbg for HmacDrbg<E> {
+    fn reseed(&mut self, additional: &[u8]) -> DrbgResult<()> {
+        let mut entropy_input = Secret::new(vec![0; ENTROPY_LEN]);
+        self.entropy.fill(&mut entropy_input)?;
+        self.update(&[&entropy_input, additional]);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    fn generate(&mut self, n: usize, additional: &[u8]) -> DrbgResult<Vec<u8>> {
+        if n > MAX_REQUEST_LEN {
+            return Err(Error::RequestTooLarge(n));
+        }
+
+        let mut additional = additional;
+        if self.prediction_resistance || self.reseed_counter > self.reseed_interval {
+            self.reseed(additional)?;
+            additional = &[];
+        }
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+
+        let mut output = Vec::with_capacity(n + Sha256::OUTPUT_LEN);
+        while output.len() < n {
+            self.v = Secret::new(HmacSha256::mac(&*self.key, &*self.v));
+            output.extend_from_slice(&self.v);
+        }
+        output.truncate(n);
+
+        self.update(&[additional]);
+        self.reseed_counter += 1;
+        Ok(output)
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+}
+
+/// Generated output as a [`prng::Rng`], panics if a reseed can not get
+/// entropy.
+impl<E: Entropy> prng::Rng for HmacDrbg<E> {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0; 4];
+        prng::Rng::fill_bytes(self, &mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        Drbg::fill_bytes(self, buf).expect("HmacDrbg: entropy source failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Fixed;
+
+    fn unhex(h: &str) -> Vec<u8> {
+        hex::decode(h).unwrap().0
+    }
+
+    // CAVP drbgvectors_no_reseed, HMAC_DRBG.txt [SHA-256], COUNT = 0
+    #[test]
+    fn test_cavp_sha256() {
+        let entropy =
+            Fixed::new(&["ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488"]);
+        let nonce = unhex("659ba96c601dc69fc902940805ec0ca8");
+        let mut drbg = HmacDrbg::with_entropy(entropy, &nonce, &[]).unwrap();
+        drbg.generate(128, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(128, &[]).unwrap(),
+            unhex(
+                "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89\
+                 d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1\
+                 07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668\
+                 961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8"
+            )
+        );
+    }
+
+    // same file, PersonalizationStringLen = 256, COUNT = 0
+    #[test]
+    fn test_cavp_sha256_personalization() {
+        let entropy =
+            Fixed::new(&["5cacc68165a2e2ee20812f35ec73a79dbf30fd475476ac0c44fc6174cdac2b55"]);
+        let nonce = unhex("6f885496c1e63af620becd9e71ecb824");
+        let personalization =
+            unhex("e72dd8590d4ed5295515c35ed6199e9d211b8f069b3058caa6670b96ef1208d0");
+        let mut drbg = HmacDrbg::with_entropy(entropy, &nonce, &personalization).unwrap();
+        drbg.generate(128, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(128, &[]).unwrap(),
+            unhex(
+                "f1012cf543f94533df27fedfbf58e5b79a3dc517a9c402bdbfc9a0c0f721f9d5\
+                 3faf4aafdc4b8f7a1b580fcaa52338d4bd95f58966a243cdcd3f446ed4bc546d\
+                 9f607b190dd69954450d16cd0e2d6437067d8b44d19a6af7a7cfa8794e5fbd72\
+                 8e8fb2f2e8db5dd4ff1aa275f35886098e80ff844886060da8b1e7137846b23b"
+            )
+        );
+    }
+
+    // CAVP drbgvectors_pr_true, HMAC_DRBG.rsp [SHA-256], COUNT = 0
+    #[test]
+    fn test_cavp_sha256_prediction_resistance() {
+        let entropy = Fixed::new(&[
+            "9969e54b4703ff31785b879a7e5c0eae0d3e309559e9fe96b0676d49d591ea4d",
+            "c60f2999100f738c10f74792676a3fc4a262d13721798046e29a295181569f54",
+            "c11d4524c9071bd3096015fcf7bc24a607f22fa065c937658a2a77a8699089f4",
+        ]);
+        let nonce = unhex("07d20d46d064757d3023cac2376127ab");
+        let mut drbg = HmacDrbg::with_entropy(entropy, &nonce, &[])
+            .unwrap()
+            .with_prediction_resistance();
+        drbg.generate(128, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(128, &[]).unwrap(),
+            unhex(
+                "abc015856094803a938dffd20da94843870ef935b82cfec17706b8f551b83850\
+                 44235dd44b599f94b39be78dd476e0cf11309c995a7334e0a78b37bc95862350\
+                 86fa3b637ba91cf8fb65efa22a589c137531aa7b2d4e2607aac27292b01c698e\
+                 6e01ae679eb87c01a89c7422d4372d6d754ababb4bf896fcb1cd09d692d0283f"
+            )
+        );
+        assert_eq!(drbg.reseed_counter(), 2);
+    }
+
+    // CAVP drbgvectors_pr_false, HMAC_DRBG.rsp [SHA-256], COUNT = 0
+    #[test]
+    fn test_cavp_sha256_reseed() {
+        let entropy = Fixed::new(&[
+            "06032cd5eed33f39265f49ecb142c511da9aff2af71203bffaf34a9ca5bd9c0d",
+            "01920a4e669ed3a85ae8a33b35a74ad7fb2a6bb4cf395ce00334a9c9a5a5d552",
+        ]);
+        let nonce = unhex("0e66f71edc43e42a45ad3c6fc6cdc4df");
+        let mut drbg = HmacDrbg::with_entropy(entropy, &nonce, &[]).unwrap();
+        drbg.reseed(&[]).unwrap();
+        drbg.generate(128, &[]).unwrap();
+        assert_eq!(
+            drbg.generate(128, &[]).unwrap(),
+            unhex(
+                "76fc79fe9b50beccc991a11b5635783a83536add03c157fb30645e611c2898bb\
+                 2b1bc215000209208cd506cb28da2a51bdb03826aaf2bd2335d576d519160842\
+                 e7158ad0949d1a9ec3e66ea1b1a064b005de914eac2e9d4f2d72a8616a802254\
+                 22918250ff66a41bd2f864a6a38cc5b6499dc43f7f2bd09e1e0f8f5885935124"
+            )
+        );
+    }
+
+    #[test]
+    fn test_os_entropy() {
+        let mut a = HmacDrbg::new(&[]).unwrap();
+        let mut b = HmacDrbg::new(&[]).unwrap();
+        assert_ne!(a.generate(32, &[]).unwrap(), b.generate(32, &[]).unwrap());
+        assert!(a.generate(MAX_REQUEST_LEN + 1, &[]).is_err());
+    }
+
+    #[test]
+    fn test_rng() {
+        let entropy = || Fixed::new(&[&"11".repeat(32)]);
+        let mut a = HmacDrbg::with_entropy(entropy(), &[], &[]).unwrap();
+        let mut b = HmacDrbg::with_entropy(entropy(), &[], &[]).unwrap();
+        assert_eq!(
+            prng::Rng::random_bytes(&mut a, 100),
+            b.generate(100, &[]).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "entropy source failed")]
+    fn test_rng_without_entropy() {
+        // the reseed of every request finds no entropy left
+        let entropy = Fixed::new(&[&"11".repeat(32)]);
+        let drbg = HmacDrbg::with_entropy(entropy, &[], &[]).unwrap();
+        prng::Rng::next_u32(&mut drbg.with_prediction_resistance());
+    }
+}
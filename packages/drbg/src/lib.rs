@@ -0,0 +1,105 @@
+//! Deterministic random bit generators of NIST SP 800-90A
+//! (https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf),
+//! CTR_DRBG over AES and HMAC_DRBG over SHA-256, seeded from [`OsRng`].
+//! Both implement [`prng::Rng`] so they can drive the oracles and attacks.
+
+use prng::{OsRng, Rng};
+use thiserror::Error;
+
+mod ctr;
+mod hmac;
+
+pub use ctr::CtrDrbg;
+pub use hmac::HmacDrbg;
+
+pub type Aes128CtrDrbg<E = OsEntropy> = CtrDrbg<128, E>;
+pub type Aes256CtrDrbg<E = OsEntropy> = CtrDrbg<256, E>;
+
+// 2^19 bits per request, for both mechanisms
+pub const MAX_REQUEST_LEN: usize = 1 << 16;
+// generate requests between two reseeds
+pub const RESEED_INTERVAL: u64 = 1 << 48;
+
+pub type DrbgResult<T> = Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Requested {0} bytes, at most {MAX_REQUEST_LEN} per request")]
+    RequestTooLarge(usize),
+
+    #[error("Input of {0} bytes is too long, at most {1}")]
+    InputTooLong(usize, usize),
+
+    #[error("Entropy source failed: {0}")]
+    Entropy(String),
+}
+
+/// Source of entropy input for instantiating and reseeding.
+pub trait Entropy {
+    fn fill(&mut self, buf: &mut [u8]) -> DrbgResult<()>;
+}
+
+/// Entropy from the operating system, through [`OsRng`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsEntropy;
+
+impl Entropy for OsEntropy {
+    fn fill(&mut self, buf: &mut [u8]) -> DrbgResult<()> {
+        OsRng.fill_bytes(buf);
+        Ok(())
+    }
+}
+
+/// Operations common to the DRBG mechanisms.
+pub trait Drbg {
+    /// Fresh entropy from the source, mixed with `additional` input.
+    fn reseed(&mut self, additional: &[u8]) -> DrbgResult<()>;
+
+    /// `n` pseudo random bytes. Reseeds first when prediction resistance
+    /// is on or the reseed interval is reached.
+    fn generate(&mut self, n: usize, additional: &[u8]) -> DrbgResult<Vec<u8>>;
+
+    // generate requests since the last (re)seed
+    fn reseed_counter(&self) -> u64;
+
+    fn fill_bytes(&mut self, buf: &mut [u8]) -> DrbgResult<()> {
+        for chunk in buf.chunks_mut(MAX_REQUEST_LEN) {
+            chunk.copy_from_slice(&self.generate(chunk.len(), &[])?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    // entropy inputs of a test vector, in order
+    pub struct Fixed(pub VecDeque<Vec<u8>>);
+
+    impl Fixed {
+        pub fn new(inputs: &[&str]) -> Self {
+            Fixed(inputs.iter().map(|h| hex::decode(*h).unwrap().0).collect())
+        }
+    }
+
+    impl Entropy for Fixed {
+        fn fill(&mut self, buf: &mut [u8]) -> DrbgResult<()> {
+            let input = self
+                .0
+                .pop_front()
+                .ok_or(Error::Entropy("no more test inputs".to_string()))?;
+            if input.len() != buf.len() {
+                return Err(Error::Entropy(format!(
+                    "{} bytes asked, test input has {}",
+                    buf.len(),
+                    input.len()
+                )));
+            }
+            buf.copy_from_slice(&input);
+            Ok(())
+        }
+    }
+}
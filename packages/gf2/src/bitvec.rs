@@ -0,0 +1,210 @@
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitXor, BitXorAssign},
+};
+
+/// Fixed length vector over GF(2), packed in 64-bit words.
+///
+/// Bit `i` is bit `i % 64` of word `i / 64`, unused bits of the last word
+/// are always zero.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    pub fn zeros(len: usize) -> Self {
+        BitVec {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Vector with only bit `i` set, the i-th unit vector.
+    pub fn unit(len: usize, i: usize) -> Self {
+        let mut v = Self::zeros(len);
+        v.set(i, true);
+        v
+    }
+
+    pub fn from_bits<I: IntoIterator<Item = bool>>(bits: I) -> Self {
+        let bits = bits.into_iter().collect::<Vec<_>>();
+        let mut v = Self::zeros(bits.len());
+        bits.iter()
+            .enumerate()
+            .filter(|(_, &b)| b)
+            .for_each(|(i, _)| v.set(i, true));
+        v
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "BitVec: index {i} out of range {}", self.len);
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "BitVec: index {i} out of range {}", self.len);
+        let mask = 1 << (i % 64);
+        if value {
+            self.words[i / 64] |= mask;
+        } else {
+            self.words[i / 64] &= !mask;
+        }
+    }
+
+    pub fn flip(&mut self, i: usize) {
+        assert!(i < self.len, "BitVec: index {i} out of range {}", self.len);
+        self.words[i / 64] ^= 1 << (i % 64);
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Index of the lowest set bit.
+    pub fn first_one(&self) -> Option<usize> {
+        self.first_one_from(0)
+    }
+
+    /// Index of the lowest set bit at or after `start`.
+    pub fn first_one_from(&self, start: usize) -> Option<usize> {
+        if start >= self.len {
+            return None;
+        }
+        let first = self.words[start / 64] & (u64::MAX << (start % 64));
+        if first != 0 {
+            return Some(start / 64 * 64 + first.trailing_zeros() as usize);
+        }
+        self.words[start / 64 + 1..]
+            .iter()
+            .position(|&w| w != 0)
+            .map(|j| {
+                let j = start / 64 + 1 + j;
+                j * 64 + self.words[j].trailing_zeros() as usize
+            })
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(j, &w)| {
+            let mut w = w;
+            std::iter::from_fn(move || {
+                (w != 0).then(|| {
+                    let i = w.trailing_zeros() as usize;
+                    w &= w - 1;
+                    j * 64 + i
+                })
+            })
+        })
+    }
+
+    /// Inner product, the parity of `self & other`.
+    pub fn dot(&self, other: &BitVec) -> bool {
+        assert_eq!(self.len, other.len, "BitVec: length mismatch");
+        self.words
+            .iter()
+            .zip(&other.words)
+            .fold(0, |acc, (a, b)| acc ^ (a & b).count_ones())
+            & 1
+            == 1
+    }
+}
+
+impl BitXorAssign<&BitVec> for BitVec {
+    fn bitxor_assign(&mut self, rhs: &BitVec) {
+        assert_eq!(self.len, rhs.len, "BitVec: length mismatch");
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+}
+
+impl BitXor for &BitVec {
+    type Output = BitVec;
+
+    fn bitxor(self, rhs: &BitVec) -> BitVec {
+        let mut v = self.clone();
+        v ^= rhs;
+        v
+    }
+}
+
+impl BitAnd for &BitVec {
+    type Output = BitVec;
+
+    fn bitand(self, rhs: &BitVec) -> BitVec {
+        assert_eq!(self.len, rhs.len, "BitVec: length mismatch");
+        BitVec {
+            words: self
+                .words
+                .iter()
+                .zip(&rhs.words)
+                .map(|(a, b)| a & b)
+                .collect(),
+            len: self.len,
+        }
+    }
+}
+
+impl Debug for BitVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bits = (0..self.len)
+            .map(|i| if self.get(i) { '1' } else { '0' })
+            .collect::<String>();
+        write!(f, "BitVec({bits})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits() {
+        let mut v = BitVec::zeros(130);
+        assert!(v.is_zero());
+        assert_eq!(v.first_one(), None);
+
+        v.set(3, true);
+        v.set(64, true);
+        v.flip(129);
+        assert!(v.get(3) && v.get(64) && v.get(129) && !v.get(4));
+        assert_eq!(v.count_ones(), 3);
+        assert_eq!(v.iter_ones().collect::<Vec<_>>(), [3, 64, 129]);
+        assert_eq!(v.first_one(), Some(3));
+        assert_eq!(v.first_one_from(4), Some(64));
+        assert_eq!(v.first_one_from(65), Some(129));
+        assert_eq!(v.first_one_from(130), None);
+
+        v.set(3, false);
+        assert_eq!(v.first_one(), Some(64));
+    }
+
+    #[test]
+    fn test_xor_and_dot() {
+        let a = BitVec::from_bits([true, true, false, true]);
+        let b = BitVec::from_bits([false, true, true, true]);
+        assert_eq!(&a ^ &b, BitVec::from_bits([true, false, true, false]));
+        assert_eq!(&a & &b, BitVec::from_bits([false, true, false, true]));
+        assert!(!a.dot(&b));
+        assert!(a.dot(&BitVec::unit(4, 0)));
+        assert_eq!(format!("{a:?}"), "BitVec(1101)");
+    }
+}
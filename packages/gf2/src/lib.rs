@@ -0,0 +1,9 @@
+//! Linear algebra over GF(2), bit vectors, bit matrices & Gaussian elimination.
+
+mod bitvec;
+mod matrix;
+mod system;
+
+pub use bitvec::BitVec;
+pub use matrix::BitMatrix;
+pub use system::{Equation, LinearSystem};
@@ -0,0 +1,150 @@
+use std::ops::Mul;
+
+use crate::{BitVec, Equation, LinearSystem};
+
+/// Dense matrix over GF(2), stored as row bit vectors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: Vec<BitVec>,
+    cols: usize,
+}
+
+impl BitMatrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        BitMatrix {
+            rows: vec![BitVec::zeros(cols); rows],
+            cols,
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        BitMatrix {
+            rows: (0..n).map(|i| BitVec::unit(n, i)).collect(),
+            cols: n,
+        }
+    }
+
+    /// Panics if the rows are not all of the same length.
+    pub fn from_rows(rows: Vec<BitVec>) -> Self {
+        let cols = rows.first().map(BitVec::len).unwrap_or(0);
+        assert!(
+            rows.iter().all(|r| r.len() == cols),
+            "BitMatrix: rows of different lengths"
+        );
+        BitMatrix { rows, cols }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &BitVec {
+        &self.rows[i]
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        self.rows[i].get(j)
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, value: bool) {
+        self.rows[i].set(j, value)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows());
+        for (i, row) in self.rows.iter().enumerate() {
+            row.iter_ones().for_each(|j| t.set(j, i, true));
+        }
+        t
+    }
+
+    pub fn rank(&self) -> usize {
+        let mut system = LinearSystem::new(self.cols);
+        self.rows.iter().for_each(|row| {
+            system.add(Equation::new(row.clone(), false));
+        });
+        system.rank()
+    }
+
+    /// A solution of `self * x = b`, `None` when there is none.
+    pub fn solve(&self, b: &BitVec) -> Option<BitVec> {
+        assert_eq!(b.len(), self.rows(), "BitMatrix: rhs length mismatch");
+        let mut system = LinearSystem::new(self.cols);
+        self.rows.iter().enumerate().for_each(|(i, row)| {
+            system.add(Equation::new(row.clone(), b.get(i)));
+        });
+        system.solve()
+    }
+}
+
+impl Mul<&BitVec> for &BitMatrix {
+    type Output = BitVec;
+
+    fn mul(self, x: &BitVec) -> BitVec {
+        BitVec::from_bits(self.rows.iter().map(|row| row.dot(x)))
+    }
+}
+
+impl Mul for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn mul(self, rhs: &BitMatrix) -> BitMatrix {
+        assert_eq!(self.cols, rhs.rows(), "BitMatrix: dimension mismatch");
+        // row i of the product is the sum of the rows of rhs picked by row i
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter_ones().fold(BitVec::zeros(rhs.cols), |mut acc, k| {
+                    acc ^= rhs.row(k);
+                    acc
+                })
+            })
+            .collect();
+        BitMatrix {
+            rows,
+            cols: rhs.cols,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[u8]]) -> BitMatrix {
+        BitMatrix::from_rows(
+            rows.iter()
+                .map(|r| BitVec::from_bits(r.iter().map(|&b| b == 1)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_mul_and_transpose() {
+        let a = matrix(&[&[1, 1, 0], &[0, 1, 1]]);
+        let x = BitVec::from_bits([true, true, true]);
+        assert_eq!(&a * &x, BitVec::from_bits([false, false]));
+
+        let at = a.transpose();
+        assert_eq!(at, matrix(&[&[1, 0], &[1, 1], &[0, 1]]));
+        assert_eq!(&a * &at, matrix(&[&[0, 1], &[1, 0]]));
+        assert_eq!(&a * &BitMatrix::identity(3), a);
+    }
+
+    #[test]
+    fn test_rank_and_solve() {
+        let a = matrix(&[&[1, 1, 0], &[0, 1, 1], &[1, 0, 1]]);
+        assert_eq!(a.rank(), 2);
+        assert_eq!(BitMatrix::identity(5).rank(), 5);
+
+        let b = BitVec::from_bits([true, false, true]);
+        let x = a.solve(&b).unwrap();
+        assert_eq!(&a * &x, b);
+        assert_eq!(a.solve(&BitVec::from_bits([true, false, false])), None);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::BitVec;
+
+/// Linear equation `coefficients . x = value` over GF(2).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equation {
+    pub coefficients: BitVec,
+    pub value: bool,
+}
+
+impl Equation {
+    pub fn new(coefficients: BitVec, value: bool) -> Self {
+        Equation {
+            coefficients,
+            value,
+        }
+    }
+}
+
+/// System of linear equations kept in row echelon form as they are added.
+///
+/// Each stored equation is the pivot of its lowest variable, a new equation
+/// is reduced by the pivots of its lowest variables until it is zero
+/// (redundant or contradictory) or becomes a new pivot. Sparse equations,
+/// which is what generators leak first, stay cheap to reduce.
+#[derive(Debug, Clone)]
+pub struct LinearSystem {
+    variables: usize,
+    pivots: Vec<Option<Equation>>,
+    rank: usize,
+    inconsistent: bool,
+}
+
+impl LinearSystem {
+    pub fn new(variables: usize) -> Self {
+        LinearSystem {
+            variables,
+            pivots: vec![None; variables],
+            rank: 0,
+            inconsistent: false,
+        }
+    }
+
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    // every variable is determined
+    pub fn is_full_rank(&self) -> bool {
+        self.rank == self.variables
+    }
+
+    // some added equation contradicted the others
+    pub fn is_inconsistent(&self) -> bool {
+        self.inconsistent
+    }
+
+    /// Add an equation, returns `true` if it raised the rank.
+    pub fn add(&mut self, mut equation: Equation) -> bool {
+        assert_eq!(
+            equation.coefficients.len(),
+            self.variables,
+            "LinearSystem: equation length mismatch"
+        );
+        let mut start = 0;
+        while let Some(i) = equation.coefficients.first_one_from(start) {
+            match &self.pivots[i] {
+                Some(pivot) => {
+                    equation.coefficients ^= &pivot.coefficients;
+                    equation.value ^= pivot.value;
+                    start = i + 1;
+                }
+                None => {
+                    self.pivots[i] = Some(equation);
+                    self.rank += 1;
+                    return true;
+                }
+            }
+        }
+        // 0 = value
+        self.inconsistent |= equation.value;
+        false
+    }
+
+    /// A solution, free variables are set to zero. `None` if inconsistent.
+    pub fn solve(&self) -> Option<BitVec> {
+        if self.inconsistent {
+            return None;
+        }
+        // a pivot only has variables above its own, back substitute from
+        // the last one
+        let mut x = BitVec::zeros(self.variables);
+        for (i, pivot) in self.pivots.iter().enumerate().rev() {
+            if let Some(pivot) = pivot {
+                // x[i] is still zero so it does not count in the product
+                let value = pivot.value ^ pivot.coefficients.dot(&x);
+                x.set(i, value);
+            }
+        }
+        Some(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn equation(bits: &[u8], value: u8) -> Equation {
+        Equation::new(BitVec::from_bits(bits.iter().map(|&b| b == 1)), value == 1)
+    }
+
+    #[test]
+    fn test_solve() {
+        // x0 ^ x1 = 1, x1 ^ x2 = 0, x0 ^ x1 ^ x2 = 0
+        let mut system = LinearSystem::new(3);
+        assert!(system.add(equation(&[1, 1, 0], 1)));
+        assert!(system.add(equation(&[0, 1, 1], 0)));
+        assert!(!system.is_full_rank());
+        assert!(system.add(equation(&[1, 1, 1], 0)));
+        assert!(system.is_full_rank());
+        assert_eq!(
+            system.solve().unwrap(),
+            BitVec::from_bits([false, true, true])
+        );
+
+        // implied by the others
+        assert!(!system.add(equation(&[1, 0, 1], 1)));
+        assert!(!system.is_inconsistent());
+        assert_eq!(system.rank(), 3);
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        let mut system = LinearSystem::new(2);
+        system.add(equation(&[1, 1], 1));
+        system.add(equation(&[1, 0], 1));
+        system.add(equation(&[0, 1], 1));
+        assert!(system.is_inconsistent());
+        assert_eq!(system.solve(), None);
+    }
+
+    #[test]
+    fn test_underdetermined() {
+        let mut system = LinearSystem::new(3);
+        system.add(equation(&[1, 0, 1], 1));
+        let x = system.solve().unwrap();
+        assert_eq!(x, BitVec::from_bits([true, false, false]));
+    }
+}
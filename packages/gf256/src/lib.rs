@@ -0,0 +1,215 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+/// x^8 + x^4 + x^3 + x + 1, the reduction polynomial used by AES (Rijndael).
+pub const RIJNDAEL_POLY: u16 = 0x11B;
+
+/// GF(2^8) with the Rijndael reduction polynomial.
+pub type Rijndael = GF256<RIJNDAEL_POLY>;
+
+/// An element of GF(2^8) reduced by the polynomial `P`.
+///
+/// `P` is written with the x^8 term included (e.g. `0x11B`) and must be
+/// irreducible for `inv` and `div` to be meaningful.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GF256<const P: u16>(pub u8);
+
+impl<const P: u16> GF256<P> {
+    // evaluated on first use, rejects polynomials which are not of degree 8
+    const DEGREE_CHECK: () = assert!(
+        P >= 0x100 && P <= 0x1FF,
+        "GF256: reduction polynomial must be of degree 8"
+    );
+
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1);
+
+    pub const fn new(v: u8) -> Self {
+        Self(v)
+    }
+
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    // addition & subtraction are both xor in characteristic 2
+    pub const fn add(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+
+    pub const fn sub(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+
+    // multiply by x, i.e. shift left and reduce
+    pub const fn xtime(self) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::DEGREE_CHECK;
+        let reduce = if self.0 & 0x80 != 0 {
+            (P & 0xFF) as u8
+        } else {
+            0
+        };
+        Self((self.0 << 1) ^ reduce)
+    }
+
+    // russian peasant multiplication
+    pub const fn mul(self, rhs: Self) -> Self {
+        let mut a = self;
+        let mut b = rhs.0;
+        let mut p = 0u8;
+        while b != 0 {
+            if b & 1 != 0 {
+                p ^= a.0;
+            }
+            a = a.xtime();
+            b >>= 1;
+        }
+        Self(p)
+    }
+
+    pub const fn pow(self, mut exp: u32) -> Self {
+        let mut base = self;
+        let mut acc = Self::ONE;
+        while exp != 0 {
+            if exp & 1 != 0 {
+                acc = acc.mul(base);
+            }
+            base = base.mul(base);
+            exp >>= 1;
+        }
+        acc
+    }
+
+    // a^254 = a^-1 since the multiplicative group has order 255.
+    // 0 has no inverse and maps to 0, as in the AES S-box.
+    pub const fn inv(self) -> Self {
+        self.pow(254)
+    }
+
+    // panics on division by zero
+    pub const fn div(self, rhs: Self) -> Self {
+        assert!(rhs.0 != 0, "GF256: division by zero");
+        self.mul(rhs.inv())
+    }
+
+    /// Table of `self * i` for every `i` in the field.
+    pub const fn mul_table(self) -> [u8; 256] {
+        let mut table = [0u8; 256];
+        let mut i = 0;
+        while i < 256 {
+            table[i] = self.mul(Self(i as u8)).0;
+            i += 1;
+        }
+        table
+    }
+}
+
+impl<const P: u16> From<u8> for GF256<P> {
+    fn from(v: u8) -> Self {
+        Self(v)
+    }
+}
+
+impl<const P: u16> From<GF256<P>> for u8 {
+    fn from(v: GF256<P>) -> Self {
+        v.0
+    }
+}
+
+impl<const P: u16> Debug for GF256<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GF256<{P:#x}>({:#04x})", self.0)
+    }
+}
+
+impl<const P: u16> Display for GF256<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#04x}", self.0)
+    }
+}
+
+macro_rules! impl_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl<const P: u16> $op for GF256<P> {
+            type Output = Self;
+
+            fn $fn(self, rhs: Self) -> Self {
+                GF256::$fn(self, rhs)
+            }
+        }
+
+        impl<const P: u16> $op_assign for GF256<P> {
+            fn $fn_assign(&mut self, rhs: Self) {
+                *self = GF256::$fn(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign);
+impl_op!(Sub, sub, SubAssign, sub_assign);
+impl_op!(Mul, mul, MulAssign, mul_assign);
+impl_op!(Div, div, DivAssign, div_assign);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rijndael_mul() {
+        // FIPS-197 section 4.2
+        assert_eq!(
+            Rijndael::new(0x57) * Rijndael::new(0x83),
+            Rijndael::new(0xc1)
+        );
+        assert_eq!(
+            Rijndael::new(0x57) * Rijndael::new(0x13),
+            Rijndael::new(0xfe)
+        );
+        assert_eq!(Rijndael::new(0x57).xtime(), Rijndael::new(0xae));
+        assert_eq!(Rijndael::new(0x8e).xtime(), Rijndael::new(0x07));
+    }
+
+    #[test]
+    fn test_rijndael_inverse() {
+        assert_eq!(Rijndael::ZERO.inv(), Rijndael::ZERO);
+        for i in 1..=255u8 {
+            let a = Rijndael::new(i);
+            assert_eq!(a * a.inv(), Rijndael::ONE, "inverse of {a}");
+            assert_eq!(a / a, Rijndael::ONE);
+        }
+        assert_eq!(Rijndael::new(0x53).inv(), Rijndael::new(0xca));
+    }
+
+    #[test]
+    fn test_pow() {
+        let g = Rijndael::new(0x03);
+        assert_eq!(g.pow(0), Rijndael::ONE);
+        assert_eq!(g.pow(255), Rijndael::ONE);
+        // 3 generates the whole multiplicative group
+        let mut seen = [false; 256];
+        for e in 0..255 {
+            seen[g.pow(e).value() as usize] = true;
+        }
+        assert_eq!(seen.iter().filter(|&&s| s).count(), 255);
+    }
+
+    #[test]
+    fn test_other_polynomial() {
+        // x^8 + x^4 + x^3 + x^2 + 1, used by QR codes & Reed-Solomon
+        type QrField = GF256<0x11D>;
+        assert_eq!(QrField::new(0x80).xtime(), QrField::new(0x1d));
+        for i in 1..=255u8 {
+            let a = QrField::new(i);
+            assert_eq!(a * a.inv(), QrField::ONE);
+        }
+    }
+
+    #[test]
+    fn test_mul_table() {
+        const TABLE: [u8; 256] = Rijndael::new(0x02).mul_table();
+        assert_eq!(TABLE[0x80], 0x1b);
+        assert_eq!(TABLE[0x01], 0x02);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::Hash;
+
+/// HMAC (RFC 2104) over any [`Hash`].
+#[derive(Debug, Clone)]
+pub struct Hmac<H: Hash> {
+    inner: H,
+    outer: H,
+}
+
+impl<H: Hash> Hmac<H> {
+    pub fn new<K: AsRef<[u8]>>(key: K) -> Self {
+        // keys longer than a block are hashed first, then zero padded
+        let key = key.as_ref();
+        let mut block = if key.len() > H::BLOCK_LEN {
+            H::digest(key)
+        } else {
+            key.to_vec()
+        };
+        block.resize(H::BLOCK_LEN, 0);
+
+        let mut inner = H::new();
+        inner.update(block.iter().map(|b| b ^ 0x36).collect::<Vec<_>>());
+        let mut outer = H::new();
+        outer.update(block.iter().map(|b| b ^ 0x5c).collect::<Vec<_>>());
+        block.iter_mut().for_each(|b| *b = 0);
+
+        Hmac { inner, outer }
+    }
+
+    pub fn update<T: AsRef<[u8]>>(&mut self, data: T) {
+        self.inner.update(data);
+    }
+
+    // H((K ^ opad) || H((K ^ ipad) || data))
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(self.inner.finalize());
+        outer.finalize()
+    }
+
+    // one shot mac of `data`
+    pub fn mac<K: AsRef<[u8]>, T: AsRef<[u8]>>(key: K, data: T) -> Vec<u8> {
+        let mut mac = Self::new(key);
+        mac.update(data);
+        mac.finalize()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::HmacSha256;
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        // test cases 1, 2 & 6
+        assert_eq!(
+            hex::encode(HmacSha256::mac([0x0b; 20], "Hi There")).unwrap(),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex::encode(HmacSha256::mac("Jefe", "what do ya want for nothing?")).unwrap(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex::encode(HmacSha256::mac(
+                [0xaa; 131],
+                "Test Using Larger Than Block-Size Key - Hash Key First"
+            ))
+            .unwrap(),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+}
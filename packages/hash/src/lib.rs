@@ -0,0 +1,26 @@
+mod hmac;
+mod sha256;
+
+pub use hmac::Hmac;
+pub use sha256::Sha256;
+
+/// Cryptographic hash function with incremental input.
+pub trait Hash: Clone {
+    // size of the internal block in bytes, used by HMAC
+    const BLOCK_LEN: usize;
+    const OUTPUT_LEN: usize;
+
+    fn new() -> Self;
+    fn update<T: AsRef<[u8]>>(&mut self, data: T);
+    fn finalize(self) -> Vec<u8>;
+
+    // one shot hash of `data`
+    fn digest<T: AsRef<[u8]>>(data: T) -> Vec<u8> {
+        let mut h = Self::new();
+        h.update(data);
+        h.finalize()
+    }
+}
+
+// HMAC-SHA256
+pub type HmacSha256 = Hmac<Sha256>;
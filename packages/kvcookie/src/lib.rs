@@ -0,0 +1,144 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+// characters with a meaning in the encoding, never allowed in keys or values
+const META_CHARS: [char; 2] = ['&', '='];
+
+pub type CookieResult<T> = Result<T, Error>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Metacharacter {0:?} not allowed in cookie keys or values")]
+    MetaCharacter(char),
+
+    #[error("Malformed pair {0:?}, expected key=value")]
+    MalformedPair(String),
+
+    #[error("Empty key")]
+    EmptyKey,
+}
+
+/// Ordered `key=value&key=value` structured cookie.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cookie {
+    pairs: Vec<(String, String)>,
+}
+
+impl Cookie {
+    pub fn new() -> Self {
+        Cookie::default()
+    }
+
+    pub fn parse<T: AsRef<str>>(value: T) -> CookieResult<Self> {
+        let value = value.as_ref();
+        if value.is_empty() {
+            return Ok(Cookie::new());
+        }
+
+        let mut cookie = Cookie::new();
+        for pair in value.split('&') {
+            let (k, v) = pair
+                .split_once('=')
+                .ok_or(Error::MalformedPair(pair.to_string()))?;
+            cookie.push(k, v)?;
+        }
+        Ok(cookie)
+    }
+
+    // appends a pair, rejecting (not stripping) metacharacters
+    pub fn push<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) -> CookieResult<()> {
+        let (key, value) = (key.as_ref(), value.as_ref());
+        if key.is_empty() {
+            return Err(Error::EmptyKey);
+        }
+        check_meta(key)?;
+        check_meta(value)?;
+        self.pairs.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    // value of the first pair with `key`
+    pub fn get<K: AsRef<str>>(&self, key: K) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key.as_ref())
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn pairs(&self) -> &[(String, String)] {
+        &self.pairs
+    }
+}
+
+impl Display for Cookie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (k, v)) in self.pairs.iter().enumerate() {
+            if i != 0 {
+                write!(f, "&")?;
+            }
+            write!(f, "{k}={v}")?;
+        }
+        Ok(())
+    }
+}
+
+/// `email=<email>&uid=10&role=user`, as in cryptopals challenge 13.
+pub fn profile_for<T: AsRef<str>>(email: T) -> CookieResult<String> {
+    let mut cookie = Cookie::new();
+    cookie.push("email", email)?;
+    cookie.push("uid", "10")?;
+    cookie.push("role", "user")?;
+    Ok(cookie.to_string())
+}
+
+//
+// Private Functions
+//
+
+fn check_meta(value: &str) -> CookieResult<()> {
+    match value.chars().find(|c| META_CHARS.contains(c)) {
+        Some(c) => Err(Error::MetaCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cookie = Cookie::parse("foo=bar&baz=qux&zap=zazzle").unwrap();
+        assert_eq!(cookie.get("foo"), Some("bar"));
+        assert_eq!(cookie.get("baz"), Some("qux"));
+        assert_eq!(cookie.get("zap"), Some("zazzle"));
+        assert_eq!(cookie.get("nope"), None);
+        assert_eq!(cookie.to_string(), "foo=bar&baz=qux&zap=zazzle");
+
+        assert_eq!(Cookie::parse("").unwrap(), Cookie::new());
+        assert_eq!(Cookie::parse("k=").unwrap().get("k"), Some(""));
+        assert_eq!(
+            Cookie::parse("foo=bar&baz"),
+            Err(Error::MalformedPair("baz".to_string()))
+        );
+        assert_eq!(Cookie::parse("a=b=c"), Err(Error::MetaCharacter('=')));
+        assert_eq!(Cookie::parse("=b"), Err(Error::EmptyKey));
+    }
+
+    #[test]
+    fn test_profile_for() {
+        assert_eq!(
+            profile_for("foo@bar.com").unwrap(),
+            "email=foo@bar.com&uid=10&role=user"
+        );
+        assert_eq!(
+            profile_for("foo@bar.com&role=admin"),
+            Err(Error::MetaCharacter('&'))
+        );
+        assert_eq!(
+            profile_for("foo@bar.com=admin"),
+            Err(Error::MetaCharacter('='))
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::cell::{Cell, RefCell};
+
+use aes::{Aes128, Aes128CBC, Modes};
+use blockanalysis::BlockMode;
+use prng::Rng;
+
+/// Encrypts under a fresh random key, with 5-10 random bytes before & after
+/// the input, using ECB or CBC (random IV) with equal probability
+/// (cryptopals challenge 11).
+pub struct EcbCbcOracle<R> {
+    rng: RefCell<R>,
+    last_mode: Cell<Option<BlockMode>>,
+}
+
+impl<R: Rng> EcbCbcOracle<R> {
+    pub fn new(rng: R) -> Self {
+        EcbCbcOracle {
+            rng: RefCell::new(rng),
+            last_mode: Cell::new(None),
+        }
+    }
+
+    pub fn encrypt(&self, input: &[u8]) -> Vec<u8> {
+        let rng = &mut *self.rng.borrow_mut();
+        let prefix_len = rng.gen_range(5..11) as usize;
+        let suffix_len = rng.gen_range(5..11) as usize;
+        let plain_text = [
+            rng.random_bytes(prefix_len).as_slice(),
+            input,
+            &rng.random_bytes(suffix_len),
+        ]
+        .concat();
+        let key = rng.random_bytes(16);
+
+        let (cipher_text, mode) = if rng.gen_bool(0.5) {
+            (Aes128::new().encrypt(plain_text, key), BlockMode::Ecb)
+        } else {
+            let iv = rng.random_bytes(16);
+            (
+                Aes128CBC::with_iv(iv).encrypt(plain_text, key),
+                BlockMode::Cbc,
+            )
+        };
+        self.last_mode.set(Some(mode));
+        // valid key & IV lengths
+        cipher_text.unwrap()
+    }
+
+    // mode of the last encryption
+    pub fn last_mode(&self) -> Option<BlockMode> {
+        self.last_mode.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drbg::HmacDrbg;
+    use prng::SeededRng;
+
+    #[test]
+    fn test_replay() {
+        let a = EcbCbcOracle::new(SeededRng::new(11));
+        let b = EcbCbcOracle::new(SeededRng::new(11));
+        for _ in 0..20 {
+            assert_eq!(a.encrypt(b"input"), b.encrypt(b"input"));
+            assert_eq!(a.last_mode(), b.last_mode());
+        }
+    }
+
+    #[test]
+    fn test_both_modes() {
+        let oracle = EcbCbcOracle::new(SeededRng::new(3));
+        let modes = (0..50)
+            .map(|_| {
+                let len = oracle.encrypt(&[0; 16]).len();
+                // 16 bytes of input, 10 to 20 random, padded
+                assert!(len == 32 || len == 48);
+                oracle.last_mode().unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(modes.contains(&BlockMode::Ecb) && modes.contains(&BlockMode::Cbc));
+    }
+
+    #[test]
+    fn test_drbg() {
+        let oracle = EcbCbcOracle::new(HmacDrbg::new(b"ecb cbc oracle").unwrap());
+        assert_ne!(oracle.encrypt(&[0; 32]), oracle.encrypt(&[0; 32]));
+    }
+}
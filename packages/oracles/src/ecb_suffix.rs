@@ -0,0 +1,57 @@
+use aes::{secret::Secret, Aes128, Modes};
+use prng::Rng;
+
+/// `AES-128-ECB(random-prefix || input || suffix, random-key)` with a key
+/// (and prefix) fixed at construction (cryptopals challenges 12 & 14).
+pub struct EcbSuffixOracle {
+    key: Secret<Vec<u8>>,
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+
+impl EcbSuffixOracle {
+    /// Random key, no prefix.
+    pub fn new<R: Rng>(mut rng: R, suffix: Vec<u8>) -> Self {
+        EcbSuffixOracle {
+            key: Secret::new(rng.random_bytes(16)),
+            prefix: vec![],
+            suffix,
+        }
+    }
+
+    /// Random key and a random count (up to 255) of random prefix bytes.
+    pub fn with_random_prefix<R: Rng>(mut rng: R, suffix: Vec<u8>) -> Self {
+        let mut oracle = Self::new(&mut rng, suffix);
+        let prefix_len = rng.gen_range(0..256) as usize;
+        oracle.prefix = rng.random_bytes(prefix_len);
+        oracle
+    }
+
+    pub fn prefix_len(&self) -> usize {
+        self.prefix.len()
+    }
+
+    pub fn encrypt(&self, input: &[u8]) -> Vec<u8> {
+        Aes128::new()
+            .encrypt([&self.prefix[..], input, &self.suffix].concat(), &*self.key)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::SeededRng;
+
+    #[test]
+    fn test_replay() {
+        let a = EcbSuffixOracle::with_random_prefix(SeededRng::new(12), b"suffix".to_vec());
+        let b = EcbSuffixOracle::with_random_prefix(SeededRng::new(12), b"suffix".to_vec());
+        assert_eq!(a.prefix_len(), b.prefix_len());
+        assert_eq!(a.encrypt(b"input"), b.encrypt(b"input"));
+
+        let c = EcbSuffixOracle::new(SeededRng::new(12), b"suffix".to_vec());
+        assert_eq!(c.prefix_len(), 0);
+        assert_eq!(c.encrypt(&[]).len(), 16);
+    }
+}
@@ -0,0 +1,9 @@
+//! Randomized encryption oracles of the challenges, all their randomness
+//! comes from an injected [`prng::Rng`] so that a run can be replayed from
+//! its seed.
+
+mod ecb_cbc;
+mod ecb_suffix;
+
+pub use ecb_cbc::EcbCbcOracle;
+pub use ecb_suffix::EcbSuffixOracle;
@@ -0,0 +1,76 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Source of Unix time (seconds), injected into whatever seeds from time so
+/// simulations can fast forward instead of sleeping.
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// Manually advanced clock, clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock(Arc<AtomicU64>);
+
+impl VirtualClock {
+    pub fn new(start: u64) -> Self {
+        VirtualClock(Arc::new(AtomicU64::new(start)))
+    }
+
+    pub fn set(&self, now: u64) {
+        self.0.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, seconds: u64) {
+        self.0.fetch_add(seconds, Ordering::SeqCst);
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_clock_shared() {
+        let clock = VirtualClock::new(1_000);
+        let service = clock.clone();
+        clock.advance(40);
+        assert_eq!(service.now(), 1_040);
+        service.set(7);
+        assert_eq!(clock.now(), 7);
+    }
+
+    #[test]
+    fn test_system_clock() {
+        // after 2023-11-14
+        assert!(SystemClock.now() > 1_700_000_000);
+    }
+}
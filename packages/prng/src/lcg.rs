@@ -0,0 +1,217 @@
+use crate::Rng;
+
+const JAVA_MULTIPLIER: u64 = 0x5_deec_e66d;
+const JAVA_INVERSE: u64 = 0xdfe0_5bcb_1365;
+const JAVA_ADDEND: u64 = 0xb;
+const JAVA_MASK: u64 = (1 << 48) - 1;
+
+/// `java.util.Random`, 48-bit LCG returning the top bits of its state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaRandom {
+    state: u64,
+}
+
+impl JavaRandom {
+    /// `new Random(seed)`, the seed is scrambled with the multiplier.
+    pub fn new(seed: i64) -> Self {
+        JavaRandom {
+            state: (seed as u64 ^ JAVA_MULTIPLIER) & JAVA_MASK,
+        }
+    }
+
+    /// Generator from its raw 48-bit state.
+    pub fn from_state(state: u64) -> Self {
+        JavaRandom {
+            state: state & JAVA_MASK,
+        }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Seed given to `new Random(seed)` (low 48 bits) to get the current
+    /// state.
+    pub fn seed(&self) -> i64 {
+        ((self.state ^ JAVA_MULTIPLIER) & JAVA_MASK) as i64
+    }
+
+    /// Step the state back, undoing one call of `next`.
+    pub fn step_back(&mut self) {
+        self.state = self
+            .state
+            .wrapping_sub(JAVA_ADDEND)
+            .wrapping_mul(JAVA_INVERSE)
+            & JAVA_MASK;
+    }
+
+    // top `bits` bits of the next state
+    fn next(&mut self, bits: u32) -> i32 {
+        self.state = self
+            .state
+            .wrapping_mul(JAVA_MULTIPLIER)
+            .wrapping_add(JAVA_ADDEND)
+            & JAVA_MASK;
+        (self.state >> (48 - bits)) as i32
+    }
+
+    pub fn next_int(&mut self) -> i32 {
+        self.next(32)
+    }
+
+    /// `nextInt(bound)`, panics unless `bound` is positive.
+    pub fn next_int_bounded(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0, "JavaRandom: bound must be positive");
+        // power of two, top bits
+        if bound & bound.wrapping_neg() == bound {
+            return ((bound as i64 * self.next(31) as i64) >> 31) as i32;
+        }
+        loop {
+            let bits = self.next(31);
+            let value = bits % bound;
+            // rejects the incomplete last interval, overflowing in Java ints
+            if bits.wrapping_sub(value).wrapping_add(bound - 1) >= 0 {
+                return value;
+            }
+        }
+    }
+
+    pub fn next_long(&mut self) -> i64 {
+        ((self.next(32) as i64) << 32).wrapping_add(self.next(32) as i64)
+    }
+
+    pub fn next_double(&mut self) -> f64 {
+        let bits = ((self.next(26) as i64) << 27) + self.next(27) as i64;
+        bits as f64 * crate::F64_UNIT
+    }
+
+    pub fn next_boolean(&mut self) -> bool {
+        self.next(1) != 0
+    }
+}
+
+impl Rng for JavaRandom {
+    fn next_u32(&mut self) -> u32 {
+        self.next_int() as u32
+    }
+
+    fn gen_f64(&mut self) -> f64 {
+        self.next_double()
+    }
+}
+
+/// glibc `random()` / `rand()` with the TYPE_0 state (`initstate` with 8
+/// bytes), a plain 31-bit LCG whose whole state is the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlibcType0 {
+    state: u32,
+}
+
+impl GlibcType0 {
+    /// `srandom(seed)`, zero is replaced by one.
+    pub fn new(seed: u32) -> Self {
+        GlibcType0 {
+            state: if seed == 0 { 1 } else { seed },
+        }
+    }
+
+    pub fn rand(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(1_103_515_245).wrapping_add(12_345) & 0x7fff_ffff;
+        self.state
+    }
+}
+
+/// MSVC `rand()`, bits 16 to 30 of a 32-bit LCG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsvcRand {
+    state: u32,
+}
+
+impl MsvcRand {
+    /// `srand(seed)`.
+    pub fn new(seed: u32) -> Self {
+        MsvcRand { state: seed }
+    }
+
+    pub fn from_state(state: u32) -> Self {
+        MsvcRand { state }
+    }
+
+    pub fn rand(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(214_013).wrapping_add(2_531_011);
+        (self.state >> 16) & 0x7fff
+    }
+}
+
+// unseeded programs behave as srand(1)
+impl Default for MsvcRand {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // outputs of OpenJDK
+    #[test]
+    fn test_java_random() {
+        let mut r = JavaRandom::new(42);
+        let ints = (0..4).map(|_| r.next_int()).collect::<Vec<_>>();
+        assert_eq!(ints, [-1170105035, 234785527, -1360544799, 205897768]);
+
+        let mut r = JavaRandom::new(42);
+        let bounded = (0..8).map(|_| r.next_int_bounded(10)).collect::<Vec<_>>();
+        assert_eq!(bounded, [0, 3, 8, 4, 0, 5, 5, 8]);
+
+        let mut r = JavaRandom::new(42);
+        assert_eq!(r.next_long(), -5025562857975149833);
+        assert_eq!(r.next_double(), 0.6832234717598454);
+        assert!(!r.next_boolean());
+        assert_eq!(r.next_int_bounded(1 << 20), 987835);
+        assert_eq!(r.next_int_bounded(1_000_000_007), 595021505);
+
+        let mut r = JavaRandom::new(-7);
+        let ints = (0..3).map(|_| r.next_int()).collect::<Vec<_>>();
+        assert_eq!(ints, [1155869324, -423064701, -1403676116]);
+
+        let mut r = JavaRandom::new(123456789);
+        let bounded = (0..3).map(|_| r.next_int_bounded(16)).collect::<Vec<_>>();
+        assert_eq!(bounded, [10, 12, 7]);
+    }
+
+    #[test]
+    fn test_java_step_back() {
+        let mut r = JavaRandom::new(42);
+        let first = r.next_int();
+        r.next_int();
+        r.step_back();
+        r.step_back();
+        assert_eq!(r.seed(), 42);
+        assert_eq!(r.next_int(), first);
+    }
+
+    // glibc with initstate(seed, state, 8)
+    #[test]
+    fn test_glibc_type0() {
+        let mut r = GlibcType0::new(42);
+        let outputs = (0..5).map(|_| r.rand()).collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [1250496027, 1116302264, 1000676753, 1668674806, 908095735]
+        );
+
+        let mut r = GlibcType0::new(0);
+        let outputs = (0..3).map(|_| r.rand()).collect::<Vec<_>>();
+        assert_eq!(outputs, [1103527590, 377401575, 662824084]);
+    }
+
+    #[test]
+    fn test_msvc_rand() {
+        // the famous first outputs of an unseeded MSVC program
+        let mut r = MsvcRand::default();
+        let outputs = (0..5).map(|_| r.rand()).collect::<Vec<_>>();
+        assert_eq!(outputs, [41, 18467, 6334, 26500, 19169]);
+    }
+}
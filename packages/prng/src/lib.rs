@@ -0,0 +1,119 @@
+//! Pseudo random number generators, NOT cryptographically secure.
+
+use std::ops::Range;
+
+mod clock;
+mod lcg;
+mod mt19937;
+mod mt19937_64;
+mod python;
+mod rngs;
+mod stream;
+
+pub use clock::{Clock, SystemClock, VirtualClock};
+pub use lcg::{GlibcType0, JavaRandom, MsvcRand};
+pub use mt19937::{untemper, Mt19937, STATE_LEN};
+pub use mt19937_64::Mt19937_64;
+pub use python::PyRandom;
+pub use rngs::{OsRng, SeededRng, SEED_VAR};
+pub use stream::Mt19937Cipher;
+
+// 2^-53
+const F64_UNIT: f64 = 1.0 / (1u64 << 53) as f64;
+
+/// Source of random numbers, the rest of the workspace is written against
+/// this trait rather than a particular generator.
+pub trait Rng {
+    fn next_u32(&mut self) -> u32;
+
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    // little endian words
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(4) {
+            let word = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+
+    fn random_bytes(&mut self, n: usize) -> Vec<u8> {
+        let mut buf = vec![0; n];
+        self.fill_bytes(&mut buf);
+        buf
+    }
+
+    /// Uniform in `[0, 1)` with 53 bits of precision.
+    fn gen_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * F64_UNIT
+    }
+
+    /// Uniform in `range`, without modulo bias (rejection sampling).
+    ///
+    /// Panics if the range is empty.
+    fn gen_range(&mut self, range: Range<u64>) -> u64 {
+        assert!(range.start < range.end, "gen_range: empty range");
+        let span = range.end - range.start;
+        // largest multiple of span, values above it are redrawn
+        let zone = u64::MAX - (u64::MAX - span + 1) % span;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return range.start + value % span;
+            }
+        }
+    }
+
+    fn gen_bool(&mut self, p: f64) -> bool {
+        self.gen_f64() < p
+    }
+}
+
+impl<R: Rng + ?Sized> Rng for &mut R {
+    fn next_u32(&mut self) -> u32 {
+        (**self).next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        (**self).fill_bytes(buf)
+    }
+
+    fn gen_f64(&mut self) -> f64 {
+        (**self).gen_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // counter, to check the provided methods
+    struct Counter(u32);
+
+    impl Rng for Counter {
+        fn next_u32(&mut self) -> u32 {
+            self.0 = self.0.wrapping_add(1);
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_provided_methods() {
+        let mut rng = Counter(0);
+        assert_eq!(rng.next_u64(), (1 << 32) | 2);
+        assert_eq!(rng.random_bytes(6), [3, 0, 0, 0, 4, 0]);
+
+        let mut rng = Counter(0);
+        for _ in 0..100 {
+            let value = rng.gen_range(10..17);
+            assert!((10..17).contains(&value));
+            assert!((0.0..1.0).contains(&rng.gen_f64()));
+        }
+        assert_eq!(rng.gen_range(u64::MAX - 1..u64::MAX), u64::MAX - 1);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::Rng;
+
+/// Words of state, also the number of outputs between two twists.
+pub const STATE_LEN: usize = 624;
+const N: usize = STATE_LEN;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_b0df;
+const UPPER_MASK: u32 = 0x8000_0000;
+const LOWER_MASK: u32 = 0x7fff_ffff;
+const INIT_MULTIPLIER: u32 = 1_812_433_253;
+// seed of the reference implementation & of std::mt19937
+const DEFAULT_SEED: u32 = 5489;
+// init_by_array
+const ARRAY_SEED: u32 = 19_650_218;
+const ARRAY_MULTIPLIER_1: u32 = 1_664_525;
+const ARRAY_MULTIPLIER_2: u32 = 1_566_083_941;
+
+/// 32-bit Mersenne Twister, bit-exact with `genrand_int32` of the reference
+/// implementation (mt19937ar.c by Matsumoto & Nishimura).
+#[derive(Debug, Clone)]
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    /// Seed like `init_genrand`, also what `std::mt19937(seed)` does.
+    pub fn new(seed: u32) -> Self {
+        let mut state = [0; N];
+        state[0] = seed;
+        for i in 1..N {
+            let prev = state[i - 1];
+            state[i] = INIT_MULTIPLIER
+                .wrapping_mul(prev ^ (prev >> 30))
+                .wrapping_add(i as u32);
+        }
+        Mt19937 { state, index: N }
+    }
+
+    /// Seed with a key of any length like `init_by_array`, as CPython does.
+    /// An empty key is taken as `[0]`.
+    pub fn from_key(key: &[u32]) -> Self {
+        let key = if key.is_empty() { &[0][..] } else { key };
+        let mut state = Self::new(ARRAY_SEED).state;
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..N.max(key.len()) {
+            let prev = state[i - 1];
+            state[i] = (state[i] ^ (prev ^ (prev >> 30)).wrapping_mul(ARRAY_MULTIPLIER_1))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j = (j + 1) % key.len();
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+        }
+        for _ in 0..N - 1 {
+            let prev = state[i - 1];
+            state[i] = (state[i] ^ (prev ^ (prev >> 30)).wrapping_mul(ARRAY_MULTIPLIER_2))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+        }
+        // non zero initial state
+        state[0] = UPPER_MASK;
+        Mt19937 { state, index: N }
+    }
+
+    /// Generator from raw (untempered) state words, `index` is the position
+    /// of the next word to output, `624` twists first.
+    pub fn from_state(state: [u32; N], index: usize) -> Self {
+        Mt19937 {
+            state,
+            index: index.min(N),
+        }
+    }
+
+    pub fn state(&self) -> (&[u32; N], usize) {
+        (&self.state, self.index)
+    }
+
+    /// First output of `Mt19937::new(seed)`, only computing the 398 state
+    /// words it depends on, for seed searches.
+    pub fn first_output(seed: u32) -> u32 {
+        let mut state = [0; M + 1];
+        state[0] = seed;
+        for i in 1..=M {
+            let prev = state[i - 1];
+            state[i] = INIT_MULTIPLIER
+                .wrapping_mul(prev ^ (prev >> 30))
+                .wrapping_add(i as u32);
+        }
+        let y = (state[0] & UPPER_MASK) | (state[1] & LOWER_MASK);
+        let mag = if y & 1 == 1 { MATRIX_A } else { 0 };
+        temper(state[M] ^ (y >> 1) ^ mag)
+    }
+
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            let mag = if y & 1 == 1 { MATRIX_A } else { 0 };
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+impl Default for Mt19937 {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+impl Rng for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+
+    // genrand_res53
+    fn gen_f64(&mut self) -> f64 {
+        let a = (self.next_u32() >> 5) as f64;
+        let b = (self.next_u32() >> 6) as f64;
+        (a * 67_108_864.0 + b) * crate::F64_UNIT
+    }
+}
+
+/// Inverse of the output tempering, the raw state word behind an output.
+pub fn untemper(mut y: u32) -> u32 {
+    y = undo_right_shift(y, 18);
+    y = undo_left_shift(y, 15, 0xefc6_0000);
+    y = undo_left_shift(y, 7, 0x9d2c_5680);
+    undo_right_shift(y, 11)
+}
+
+//
+// Private Functions
+//
+
+fn temper(mut y: u32) -> u32 {
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c_5680;
+    y ^= (y << 15) & 0xefc6_0000;
+    y ^ (y >> 18)
+}
+
+// y = x ^ (x >> shift), each pass recovers `shift` more high bits
+fn undo_right_shift(y: u32, shift: u32) -> u32 {
+    (0..32 / shift).fold(y, |x, _| y ^ (x >> shift))
+}
+
+// y = x ^ ((x << shift) & mask), each pass recovers `shift` more low bits
+fn undo_left_shift(y: u32, shift: u32, mask: u32) -> u32 {
+    (0..32 / shift).fold(y, |x, _| y ^ ((x << shift) & mask))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // values from std::mt19937
+    #[test]
+    fn test_genrand_int32() {
+        let mut mt = Mt19937::default();
+        let outputs = (0..5).map(|_| mt.next_u32()).collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [3499211612, 581869302, 3890346734, 3586334585, 545404204]
+        );
+
+        let mut mt = Mt19937::new(1131464071);
+        let outputs = (0..3).map(|_| mt.next_u32()).collect::<Vec<_>>();
+        assert_eq!(outputs, [3521569528, 1101990581, 1076301704]);
+    }
+
+    #[test]
+    fn test_10000th_output() {
+        // required by the C++ standard for a default constructed mt19937
+        let mut mt = Mt19937::default();
+        (0..9999).for_each(|_| {
+            mt.next_u32();
+        });
+        assert_eq!(mt.next_u32(), 4123659995);
+    }
+
+    #[test]
+    fn test_init_by_array() {
+        // mt19937ar.out of the reference implementation
+        let mut mt = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+        let outputs = (0..5).map(|_| mt.next_u32()).collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [1067595299, 955945823, 477289528, 4107218783, 4228976476]
+        );
+        assert_eq!(
+            Mt19937::from_key(&[]).next_u32(),
+            Mt19937::from_key(&[0]).next_u32()
+        );
+    }
+
+    #[test]
+    fn test_first_output() {
+        for seed in [0, 1, 5489, 1_700_000_000, u32::MAX] {
+            assert_eq!(Mt19937::first_output(seed), Mt19937::new(seed).next_u32());
+        }
+    }
+
+    #[test]
+    fn test_untemper() {
+        let mut mt = Mt19937::new(42);
+        for y in [0, 1, u32::MAX, 0x8000_0000, 0x1234_5678] {
+            assert_eq!(untemper(temper(y)), y);
+        }
+        for _ in 0..1000 {
+            let y = mt.next_u32();
+            assert_eq!(untemper(temper(y)), y);
+            assert_eq!(temper(untemper(y)), y);
+        }
+    }
+
+    #[test]
+    fn test_from_state() {
+        let mut mt = Mt19937::new(7);
+        mt.next_u32();
+        let (state, index) = mt.state();
+        let mut copy = Mt19937::from_state(*state, index);
+        assert!((0..1000).all(|_| mt.next_u32() == copy.next_u32()));
+    }
+
+    #[test]
+    fn test_genrand_res53() {
+        assert_eq!(Mt19937::default().gen_f64(), 0.8147236863931789);
+    }
+}
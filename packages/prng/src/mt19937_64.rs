@@ -0,0 +1,165 @@
+use crate::Rng;
+
+const NN: usize = 312;
+const MM: usize = 156;
+const MATRIX_A: u64 = 0xb502_6f5a_a966_19e9;
+// most significant 33 bits
+const UPPER_MASK: u64 = 0xffff_ffff_8000_0000;
+// least significant 31 bits
+const LOWER_MASK: u64 = 0x7fff_ffff;
+const INIT_MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+const DEFAULT_SEED: u64 = 5489;
+// init_by_array64
+const ARRAY_SEED: u64 = 19_650_218;
+const ARRAY_MULTIPLIER_1: u64 = 3_935_559_000_370_003_845;
+const ARRAY_MULTIPLIER_2: u64 = 2_862_933_555_777_941_757;
+
+/// 64-bit Mersenne Twister, bit-exact with `genrand64_int64` of the
+/// reference implementation (mt19937-64.c).
+#[derive(Debug, Clone)]
+pub struct Mt19937_64 {
+    state: [u64; NN],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    /// Seed like `init_genrand64`, also what `std::mt19937_64(seed)` does.
+    pub fn new(seed: u64) -> Self {
+        let mut state = [0; NN];
+        state[0] = seed;
+        for i in 1..NN {
+            let prev = state[i - 1];
+            state[i] = INIT_MULTIPLIER
+                .wrapping_mul(prev ^ (prev >> 62))
+                .wrapping_add(i as u64);
+        }
+        Mt19937_64 { state, index: NN }
+    }
+
+    /// Seed with a key of any length like `init_by_array64`. An empty key
+    /// is taken as `[0]`.
+    pub fn from_key(key: &[u64]) -> Self {
+        let key = if key.is_empty() { &[0][..] } else { key };
+        let mut state = Self::new(ARRAY_SEED).state;
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..NN.max(key.len()) {
+            let prev = state[i - 1];
+            state[i] = (state[i] ^ (prev ^ (prev >> 62)).wrapping_mul(ARRAY_MULTIPLIER_1))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u64);
+            i += 1;
+            j = (j + 1) % key.len();
+            if i >= NN {
+                state[0] = state[NN - 1];
+                i = 1;
+            }
+        }
+        for _ in 0..NN - 1 {
+            let prev = state[i - 1];
+            state[i] = (state[i] ^ (prev ^ (prev >> 62)).wrapping_mul(ARRAY_MULTIPLIER_2))
+                .wrapping_sub(i as u64);
+            i += 1;
+            if i >= NN {
+                state[0] = state[NN - 1];
+                i = 1;
+            }
+        }
+        state[0] = 1 << 63;
+        Mt19937_64 { state, index: NN }
+    }
+
+    fn twist(&mut self) {
+        for i in 0..NN {
+            let x = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % NN] & LOWER_MASK);
+            let mag = if x & 1 == 1 { MATRIX_A } else { 0 };
+            self.state[i] = self.state[(i + MM) % NN] ^ (x >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+impl Default for Mt19937_64 {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+impl Rng for Mt19937_64 {
+    // upper half, the better bits
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.index >= NN {
+            self.twist();
+        }
+        let mut x = self.state[self.index];
+        self.index += 1;
+
+        x ^= (x >> 29) & 0x5555_5555_5555_5555;
+        x ^= (x << 17) & 0x71d6_7fff_eda6_0000;
+        x ^= (x << 37) & 0xfff7_eee0_0000_0000;
+        x ^ (x >> 43)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // values from std::mt19937_64
+    #[test]
+    fn test_genrand64_int64() {
+        let mut mt = Mt19937_64::default();
+        let outputs = (0..3).map(|_| mt.next_u64()).collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [
+                14514284786278117030,
+                4620546740167642908,
+                13109570281517897720
+            ]
+        );
+
+        let mut mt = Mt19937_64::new(42);
+        let outputs = (0..3).map(|_| mt.next_u64()).collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [
+                13930160852258120406,
+                11788048577503494824,
+                13874630024467741450
+            ]
+        );
+    }
+
+    #[test]
+    fn test_init_by_array64() {
+        // mt19937-64.out of the reference implementation
+        let mut mt = Mt19937_64::from_key(&[0x12345, 0x23456, 0x34567, 0x45678]);
+        let outputs = (0..3).map(|_| mt.next_u64()).collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [
+                7266447313870364031,
+                4946485549665804864,
+                16945909448695747420
+            ]
+        );
+    }
+
+    #[test]
+    fn test_10000th_output() {
+        let mut mt = Mt19937_64::default();
+        (0..9999).for_each(|_| {
+            mt.next_u64();
+        });
+        assert_eq!(mt.next_u64(), 9981545732273789042);
+    }
+
+    #[test]
+    fn test_genrand64_res53() {
+        assert_eq!(Mt19937_64::default().gen_f64(), 0.7868209548678019);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{Mt19937, Rng};
+
+/// CPython's `random.Random`, an MT19937 with CPython's seeding and output
+/// conversions, to reproduce (or attack) Python programs.
+#[derive(Debug, Clone)]
+pub struct PyRandom {
+    mt: Mt19937,
+}
+
+impl PyRandom {
+    /// `random.seed(seed)` for an int, the absolute value split into 32-bit
+    /// words (least significant first) is the `init_by_array` key.
+    pub fn new(seed: i128) -> Self {
+        let n = seed.unsigned_abs();
+        let words = (128 - n.leading_zeros()).div_ceil(32).max(1);
+        let key = (0..words)
+            .map(|i| (n >> (32 * i)) as u32)
+            .collect::<Vec<_>>();
+        PyRandom {
+            mt: Mt19937::from_key(&key),
+        }
+    }
+
+    pub fn from_mt(mt: Mt19937) -> Self {
+        PyRandom { mt }
+    }
+
+    pub fn into_inner(self) -> Mt19937 {
+        self.mt
+    }
+
+    /// `random.random()`, 53-bit double in `[0, 1)` from two outputs.
+    pub fn random(&mut self) -> f64 {
+        self.mt.gen_f64()
+    }
+
+    /// `random.getrandbits(k)` as 32-bit words, least significant first.
+    ///
+    /// One output per word, a partial last word keeps the top bits of its
+    /// output.
+    pub fn getrandbits(&mut self, k: usize) -> Vec<u32> {
+        (0..k.div_ceil(32))
+            .map(|i| {
+                let bits = (k - 32 * i).min(32);
+                self.mt.next_u32() >> (32 - bits)
+            })
+            .collect()
+    }
+}
+
+impl Rng for PyRandom {
+    fn next_u32(&mut self) -> u32 {
+        self.mt.next_u32()
+    }
+
+    fn gen_f64(&mut self) -> f64 {
+        self.random()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // reassemble words into an integer
+    fn value(words: &[u32]) -> u128 {
+        words
+            .iter()
+            .rev()
+            .fold(0, |acc, &w| (acc << 32) | w as u128)
+    }
+
+    // expected values from CPython 3
+    #[test]
+    fn test_seed_and_random() {
+        let mut r = PyRandom::new(42);
+        assert_eq!(r.random(), 0.6394267984578837);
+        assert_eq!(r.random(), 0.025010755222666936);
+
+        assert_eq!(PyRandom::new(-42).next_u32(), 2746317213);
+        assert_eq!(PyRandom::new(0).next_u32(), 3626764237);
+        assert_eq!(PyRandom::new((1 << 64) + 5).next_u32(), 2192917427);
+
+        // random.seed(0x456_00000345_00000234_00000123) is init_by_array's test key
+        let seed = 0x456_0000_0345_0000_0234_0000_0123;
+        assert_eq!(PyRandom::new(seed).next_u32(), 1067595299);
+    }
+
+    #[test]
+    fn test_getrandbits() {
+        let mut r = PyRandom::new(42);
+        assert_eq!(value(&r.getrandbits(8)), 163);
+        assert_eq!(value(&r.getrandbits(40)), 26247967103);
+        assert_eq!(value(&r.getrandbits(100)), 257086819302815227845767741691);
+        assert_eq!(value(&r.getrandbits(64)), 13585496030504862185);
+        assert!(r.getrandbits(0).is_empty());
+    }
+}
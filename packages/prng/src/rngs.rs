@@ -0,0 +1,136 @@
+use std::{env, fs::File, io::Read};
+
+use crate::{Mt19937_64, Rng};
+
+/// Environment variable with the seed of the randomized tests and
+/// challenges, see [`SeededRng::from_test_env`].
+pub const SEED_VAR: &str = "CRYPTOPALS_SEED";
+
+/// Randomness from the operating system (`/dev/urandom`).
+///
+/// Panics if it can not be read, there is nothing sensible to fall back to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsRng;
+
+impl Rng for OsRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0; 4];
+        self.fill_bytes(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0; 8];
+        self.fill_bytes(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        File::open("/dev/urandom")
+            .and_then(|mut f| f.read_exact(buf))
+            .expect("OsRng: could not read /dev/urandom");
+    }
+}
+
+/// Deterministic generator which remembers its seed, for randomized runs
+/// that can be replayed exactly. NOT cryptographically secure.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    seed: u64,
+    rng: Mt19937_64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        SeededRng {
+            seed,
+            rng: Mt19937_64::new(seed),
+        }
+    }
+
+    /// Random seed from the operating system.
+    pub fn from_os() -> Self {
+        Self::new(OsRng.next_u64())
+    }
+
+    /// Seed from the environment variable `var` (decimal or `0x` hex) when
+    /// set, otherwise a random one. The seed is logged to stderr, setting
+    /// `var` to it replays the run.
+    pub fn from_env(var: &str) -> Self {
+        let rng = match env::var(var).ok().as_deref().and_then(parse_seed) {
+            Some(seed) => Self::new(seed),
+            None => Self::from_os(),
+        };
+        eprintln!("{var}={:#x}", rng.seed);
+        rng
+    }
+
+    /// [`Self::from_env`] on [`SEED_VAR`], for tests whose failing runs are
+    /// replayed with `CRYPTOPALS_SEED=<seed> cargo test`.
+    pub fn from_test_env() -> Self {
+        Self::from_env(SEED_VAR)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+}
+
+//
+// Private Functions
+//
+
+fn parse_seed(value: &str) -> Option<u64> {
+    let value = value.trim();
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_replay() {
+        let mut a = SeededRng::from_os();
+        let mut b = SeededRng::new(a.seed());
+        assert_eq!(a.random_bytes(100), b.random_bytes(100));
+        assert_eq!(a.gen_range(0..1000), b.gen_range(0..1000));
+    }
+
+    #[test]
+    fn test_from_env() {
+        env::set_var("PRNG_TEST_SEED", "0x2a");
+        assert_eq!(SeededRng::from_env("PRNG_TEST_SEED").seed(), 42);
+        env::set_var("PRNG_TEST_SEED", "not a seed");
+        // falls back to a random seed
+        SeededRng::from_env("PRNG_TEST_SEED");
+        assert_eq!(parse_seed(" 17 "), Some(17));
+    }
+
+    #[test]
+    fn test_os_rng() {
+        let mut rng = OsRng;
+        assert_ne!(rng.random_bytes(32), rng.random_bytes(32));
+
+        // usable through a mutable reference
+        fn draw<R: Rng>(mut rng: R) -> u64 {
+            rng.gen_range(5..6)
+        }
+        assert_eq!(draw(&mut rng), 5);
+        let dynamic: &mut dyn Rng = &mut SeededRng::new(1);
+        assert_eq!(draw(dynamic), 5);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{Mt19937, Rng};
+
+/// Toy stream cipher keyed by a 16-bit MT19937 seed (cryptopals challenge
+/// 24), the keystream is the generator's outputs as little endian bytes.
+///
+/// Same interface as the AES stream modes, a 2 byte key (little endian
+/// seed). Trivially brute forced, only there to be broken.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mt19937Cipher;
+
+impl Mt19937Cipher {
+    pub fn new() -> Self {
+        Mt19937Cipher
+    }
+
+    /// `data ^ keystream[offset..]`.
+    pub fn apply_keystream_at<T: AsRef<[u8]>, K: AsRef<[u8]>>(
+        &self,
+        data: T,
+        key: K,
+        offset: usize,
+    ) -> anyhow::Result<Vec<u8>> {
+        let key: [u8; 2] = key.as_ref().try_into().map_err(|_| {
+            anyhow::anyhow!(
+                "Mt19937Cipher: key should be 2 bytes, got {}",
+                key.as_ref().len()
+            )
+        })?;
+        let mut rng = Mt19937::new(u16::from_le_bytes(key) as u32);
+        (0..offset / 4).for_each(|_| {
+            rng.next_u32();
+        });
+
+        let data = data.as_ref();
+        let keystream = rng.random_bytes(offset % 4 + data.len());
+        Ok(data
+            .iter()
+            .zip(&keystream[offset % 4..])
+            .map(|(d, k)| d ^ k)
+            .collect())
+    }
+
+    pub fn encrypt<P: AsRef<[u8]>, K: AsRef<[u8]>>(
+        &self,
+        plain_text: P,
+        key: K,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.apply_keystream_at(plain_text, key, 0)
+    }
+
+    pub fn decrypt<C: AsRef<[u8]>, K: AsRef<[u8]>>(
+        &self,
+        cipher_text: C,
+        key: K,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.apply_keystream_at(cipher_text, key, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = Mt19937Cipher::new();
+        let key = 0xbeefu16.to_le_bytes();
+        let plain_text = b"attack at dawn, or maybe a little later";
+        let cipher_text = cipher.encrypt(plain_text, key).unwrap();
+        assert_ne!(&cipher_text[..], &plain_text[..]);
+        assert_eq!(cipher.decrypt(&cipher_text, key).unwrap(), plain_text);
+
+        // keystream bytes are the outputs in little endian
+        let first = Mt19937::new(0xbeef).next_u32().to_le_bytes();
+        assert_eq!(cipher.encrypt([0; 4], key).unwrap(), first);
+
+        assert!(cipher.encrypt(plain_text, [1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_keystream_at() {
+        let cipher = Mt19937Cipher::new();
+        let data = [0x55; 40];
+        let whole = cipher.encrypt(data, [7, 7]).unwrap();
+        for offset in [0, 1, 4, 13, 39] {
+            let part = cipher
+                .apply_keystream_at(&data[offset..], [7, 7], offset)
+                .unwrap();
+            assert_eq!(part, &whole[offset..]);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! RC4 stream cipher, broken, for demonstrating its keystream biases.
+
+/// RC4 keystream generator, the key schedule followed by the PRGA.
+#[derive(Clone)]
+pub struct Rc4State {
+    s: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4State {
+    /// Key schedule (KSA), panics on an empty key, use [`Rc4`] for checked
+    /// keys.
+    pub fn new(key: &[u8]) -> Self {
+        assert!(!key.is_empty(), "Rc4State: empty key");
+        let mut s = [0u8; 256];
+        s.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+
+        let mut j = 0u8;
+        for i in 0..256 {
+            j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+            s.swap(i, j as usize);
+        }
+        Rc4State { s, i: 0, j: 0 }
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        self.i = self.i.wrapping_add(1);
+        self.j = self.j.wrapping_add(self.s[self.i as usize]);
+        self.s.swap(self.i as usize, self.j as usize);
+        self.s[self.s[self.i as usize].wrapping_add(self.s[self.j as usize]) as usize]
+    }
+
+    pub fn skip(&mut self, n: usize) {
+        (0..n).for_each(|_| {
+            self.next_byte();
+        });
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        data.iter_mut().for_each(|b| *b ^= self.next_byte());
+    }
+}
+
+/// RC4 with the same interface as the other stream ciphers, optionally
+/// dropping the first `skip` keystream bytes (RC4-drop[n]) which carry the
+/// strongest biases.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rc4 {
+    skip: usize,
+}
+
+impl Rc4 {
+    pub fn new() -> Self {
+        Rc4 { skip: 0 }
+    }
+
+    pub fn with_skip(skip: usize) -> Self {
+        Rc4 { skip }
+    }
+
+    /// `n` keystream bytes, after the skipped ones.
+    pub fn keystream<K: AsRef<[u8]>>(&self, key: K, n: usize) -> anyhow::Result<Vec<u8>> {
+        self.apply_keystream_at(vec![0; n], key, 0)
+    }
+
+    /// `data ^ keystream[offset..]`.
+    pub fn apply_keystream_at<T: AsRef<[u8]>, K: AsRef<[u8]>>(
+        &self,
+        data: T,
+        key: K,
+        offset: usize,
+    ) -> anyhow::Result<Vec<u8>> {
+        let key = key.as_ref();
+        if key.is_empty() || key.len() > 256 {
+            anyhow::bail!("RC4: key should be 1 to 256 bytes, got {}", key.len());
+        }
+        let mut state = Rc4State::new(key);
+        state.skip(self.skip + offset);
+
+        let mut data = data.as_ref().to_vec();
+        state.apply_keystream(&mut data);
+        Ok(data)
+    }
+
+    pub fn encrypt<P: AsRef<[u8]>, K: AsRef<[u8]>>(
+        &self,
+        plain_text: P,
+        key: K,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.apply_keystream_at(plain_text, key, 0)
+    }
+
+    pub fn decrypt<C: AsRef<[u8]>, K: AsRef<[u8]>>(
+        &self,
+        cipher_text: C,
+        key: K,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.apply_keystream_at(cipher_text, key, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rc4_vectors() {
+        let cases: [(&[u8], &[u8], &[u8]); 3] = [
+            (
+                b"Key",
+                b"Plaintext",
+                &[0xbb, 0xf3, 0x16, 0xe8, 0xd9, 0x40, 0xaf, 0x0a, 0xd3],
+            ),
+            (b"Wiki", b"pedia", &[0x10, 0x21, 0xbf, 0x04, 0x20]),
+            (
+                b"Secret",
+                b"Attack at dawn",
+                &[
+                    0x45, 0xa0, 0x1f, 0x64, 0x5f, 0xc3, 0x5b, 0x38, 0x35, 0x52, 0x54, 0x4b, 0x9b,
+                    0xf5,
+                ],
+            ),
+        ];
+        let rc4 = Rc4::new();
+        for (key, plain_text, cipher_text) in cases {
+            assert_eq!(rc4.encrypt(plain_text, key).unwrap(), cipher_text);
+            assert_eq!(rc4.decrypt(cipher_text, key).unwrap(), plain_text);
+        }
+        assert!(rc4.encrypt(b"data", b"").is_err());
+        assert!(rc4.encrypt(b"data", [0; 257]).is_err());
+    }
+
+    // RFC 6229, 40-bit key 0x0102030405
+    #[test]
+    fn test_rfc6229_offsets() {
+        let key = [1, 2, 3, 4, 5];
+        let rc4 = Rc4::new();
+        assert_eq!(
+            rc4.keystream(key, 16).unwrap(),
+            [
+                0xb2, 0x39, 0x63, 0x05, 0xf0, 0x3d, 0xc0, 0x27, 0xcc, 0xc3, 0x52, 0x4a, 0x0a, 0x11,
+                0x18, 0xa8
+            ]
+        );
+
+        let offset_240 = [
+            0x28, 0xcb, 0x11, 0x32, 0xc9, 0x6c, 0xe2, 0x86, 0x42, 0x1d, 0xca, 0xad, 0xb8, 0xb6,
+            0x9e, 0xae,
+        ];
+        let offset_256 = [
+            0x1c, 0xfc, 0xf6, 0x2b, 0x03, 0xed, 0xdb, 0x64, 0x1d, 0x77, 0xdf, 0xcf, 0x7f, 0x8d,
+            0x8c, 0x93,
+        ];
+        assert_eq!(
+            rc4.apply_keystream_at([0; 16], key, 240).unwrap(),
+            offset_240
+        );
+        assert_eq!(Rc4::with_skip(256).keystream(key, 16).unwrap(), offset_256);
+        assert_eq!(
+            Rc4::with_skip(240).keystream(key, 32).unwrap()[16..],
+            offset_256
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use aes::secret::Secret;
+use gf256::Rijndael;
+use prng::Rng;
+use thiserror::Error;
+
+type Gf = Rijndael;
+
+pub type ShamirResult<T> = Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid threshold {threshold}, should be between 1 and number of shares ({shares})")]
+    InvalidThreshold { threshold: usize, shares: usize },
+
+    #[error("Share id 0 is reserved for the secret")]
+    ZeroShareId,
+
+    #[error("Duplicate share id ({0})")]
+    DuplicateShareId(u8),
+
+    #[error("Not enough shares, need {needed} got {got}")]
+    NotEnoughShares { needed: usize, got: usize },
+
+    #[error("Shares are not from the same split")]
+    MismatchedShares,
+
+    #[error("Malformed share encoding")]
+    MalformedShare,
+
+    #[error(transparent)]
+    Hex {
+        #[from]
+        source: hex::Error,
+    },
+
+    #[error("Invalid base64 share - {0}")]
+    Base64(anyhow::Error),
+}
+
+/// One share of a split secret.
+///
+/// Each byte of the secret is the constant term of its own random polynomial
+/// of degree `threshold - 1`, and `data[i]` is that polynomial evaluated at `id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub id: u8,
+    pub threshold: u8,
+    pub data: Vec<u8>,
+}
+
+impl Share {
+    // compact encoding: threshold || id || data
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&[self.threshold, self.id], self.data.as_slice()].concat()
+    }
+
+    pub fn from_bytes<T: AsRef<[u8]>>(value: T) -> ShamirResult<Self> {
+        match value.as_ref() {
+            [threshold, id, data @ ..] if *threshold != 0 && *id != 0 => Ok(Share {
+                id: *id,
+                threshold: *threshold,
+                data: data.to_vec(),
+            }),
+            _ => Err(Error::MalformedShare),
+        }
+    }
+
+    pub fn to_hex(&self) -> ShamirResult<String> {
+        Ok(hex::encode(self.to_bytes())?)
+    }
+
+    pub fn from_hex<T: AsRef<[u8]>>(value: T) -> ShamirResult<Self> {
+        Self::from_bytes(hex::decode(value)?)
+    }
+
+    pub fn to_base64(&self) -> String {
+        naivebase64::encode(self.to_bytes())
+    }
+
+    pub fn from_base64<T: AsRef<[u8]>>(value: T) -> ShamirResult<Self> {
+        Self::from_bytes(naivebase64::decode(value).map_err(Error::Base64)?)
+    }
+}
+
+/// Split `secret` into `shares` shares with ids `1..=shares`, any `threshold`
+/// of which recover it. Coefficients are drawn from `rng`, which should be
+/// cryptographically secure ([`prng::OsRng`] or a DRBG) outside of tests.
+pub fn split<T: AsRef<[u8]>, R: Rng>(
+    secret: T,
+    threshold: usize,
+    shares: usize,
+    mut rng: R,
+) -> ShamirResult<Vec<Share>> {
+    if shares > 255 {
+        return Err(Error::InvalidThreshold { threshold, shares });
+    }
+    let ids = (1..=shares as u8).collect::<Vec<_>>();
+    split_with_ids(secret, threshold, &ids, |buf| rng.fill_bytes(buf))
+}
+
+/// Same as [`split`] but with caller chosen share ids and random source,
+/// `fill` should fill the given buffer with random bytes.
+pub fn split_with_ids<T, F>(
+    secret: T,
+    threshold: usize,
+    ids: &[u8],
+    mut fill: F,
+) -> ShamirResult<Vec<Share>>
+where
+    T: AsRef<[u8]>,
+    F: FnMut(&mut [u8]),
+{
+    if threshold == 0 || threshold > ids.len() || ids.len() > 255 {
+        return Err(Error::InvalidThreshold {
+            threshold,
+            shares: ids.len(),
+        });
+    }
+    check_ids(ids.iter().copied())?;
+
+    let secret = secret.as_ref();
+    let mut shares = ids
+        .iter()
+        .map(|&id| Share {
+            id,
+            threshold: threshold as u8,
+            data: Vec::with_capacity(secret.len()),
+        })
+        .collect::<Vec<_>>();
+
+    // coefficients a[1..k] for the polynomial of a single secret byte,
+    // a[0] being the secret byte itself
+    let mut coeffs = Secret::new(vec![0u8; threshold]);
+    for &s in secret {
+        coeffs[0] = s;
+        fill(&mut coeffs[1..]);
+
+        for share in shares.iter_mut() {
+            share
+                .data
+                .push(eval_poly(&coeffs, Gf::new(share.id)).value());
+        }
+    }
+
+    Ok(shares)
+}
+
+/// Recover the secret from at least `threshold` shares using Lagrange
+/// interpolation at x = 0.
+pub fn combine(shares: &[Share]) -> ShamirResult<Vec<u8>> {
+    let first = shares
+        .first()
+        .ok_or(Error::NotEnoughShares { needed: 1, got: 0 })?;
+    if shares
+        .iter()
+        .any(|s| s.threshold != first.threshold || s.data.len() != first.data.len())
+    {
+        return Err(Error::MismatchedShares);
+    }
+    if shares.len() < first.threshold as usize {
+        return Err(Error::NotEnoughShares {
+            needed: first.threshold as usize,
+            got: shares.len(),
+        });
+    }
+    check_ids(shares.iter().map(|s| s.id))?;
+
+    // l_j(0) = prod_{m != j} x_m / (x_m - x_j), subtraction being xor
+    let basis = shares
+        .iter()
+        .map(|sj| {
+            shares
+                .iter()
+                .filter(|sm| sm.id != sj.id)
+                .fold(Gf::ONE, |acc, sm| {
+                    acc * (Gf::new(sm.id) / (Gf::new(sm.id) - Gf::new(sj.id)))
+                })
+        })
+        .collect::<Vec<_>>();
+
+    Ok((0..first.data.len())
+        .map(|i| {
+            shares
+                .iter()
+                .zip(&basis)
+                .fold(Gf::ZERO, |acc, (s, &l)| acc + Gf::new(s.data[i]) * l)
+                .value()
+        })
+        .collect())
+}
+
+//
+// Private Functions
+//
+
+// horner's method
+fn eval_poly(coeffs: &[u8], x: Gf) -> Gf {
+    coeffs
+        .iter()
+        .rev()
+        .fold(Gf::ZERO, |acc, &c| acc * x + Gf::new(c))
+}
+
+fn check_ids<I: Iterator<Item = u8>>(ids: I) -> ShamirResult<()> {
+    let mut seen = [false; 256];
+    for id in ids {
+        if id == 0 {
+            return Err(Error::ZeroShareId);
+        }
+        if seen[id as usize] {
+            return Err(Error::DuplicateShareId(id));
+        }
+        seen[id as usize] = true;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::SeededRng;
+
+    fn counter_fill() -> impl FnMut(&mut [u8]) {
+        let mut c = 0u8;
+        move |buf: &mut [u8]| {
+            for b in buf {
+                c = c.wrapping_mul(31).wrapping_add(7);
+                *b = c;
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_polynomial() {
+        // f(x) = 0x42 + 0x01 * x
+        let shares = split_with_ids([0x42], 2, &[1, 2, 3], |buf| buf.fill(1)).unwrap();
+        assert_eq!(shares[0].data, [0x43]);
+        assert_eq!(shares[1].data, [0x40]);
+        assert_eq!(shares[2].data, [0x41]);
+        assert_eq!(combine(&shares[1..]).unwrap(), [0x42]);
+    }
+
+    #[test]
+    fn test_any_k_of_n() {
+        let secret = b"YELLOW SUBMARINE";
+        let shares = split_with_ids(secret, 3, &[1, 2, 3, 4, 5], counter_fill()).unwrap();
+
+        for a in 0..5 {
+            for b in (a + 1)..5 {
+                for c in (b + 1)..5 {
+                    let picked = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    assert_eq!(combine(&picked).unwrap(), secret);
+                }
+            }
+        }
+        assert_eq!(combine(&shares).unwrap(), secret);
+        assert!(matches!(
+            combine(&shares[..2]),
+            Err(Error::NotEnoughShares { needed: 3, got: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_ids() {
+        assert!(matches!(
+            split_with_ids("a", 2, &[1, 0], counter_fill()),
+            Err(Error::ZeroShareId)
+        ));
+        assert!(matches!(
+            split_with_ids("a", 2, &[7, 7], counter_fill()),
+            Err(Error::DuplicateShareId(7))
+        ));
+        assert!(matches!(
+            split_with_ids("a", 3, &[1, 2], counter_fill()),
+            Err(Error::InvalidThreshold { .. })
+        ));
+    }
+
+    #[test]
+    fn test_share_encoding() {
+        let shares = split("attack at dawn", 2, 3, SeededRng::from_test_env()).unwrap();
+        for share in &shares {
+            assert_eq!(&Share::from_hex(share.to_hex().unwrap()).unwrap(), share);
+            assert_eq!(&Share::from_base64(share.to_base64()).unwrap(), share);
+        }
+        assert!(matches!(Share::from_bytes([2]), Err(Error::MalformedShare)));
+        assert!(Share::from_hex("zz").is_err());
+    }
+
+    #[test]
+    fn test_split_aes_key() {
+        use aes::{Aes128, Modes};
+
+        let key = b"YELLOW SUBMARINE";
+        let cipher_text = Aes128::new().encrypt("operators only", key).unwrap();
+
+        let shares = split(key, 2, 4, SeededRng::from_test_env()).unwrap();
+        let encoded = [shares[3].to_base64(), shares[1].to_base64()];
+        let decoded = encoded
+            .iter()
+            .map(|s| Share::from_base64(s).unwrap())
+            .collect::<Vec<_>>();
+        let recovered = combine(&decoded).unwrap();
+
+        assert_eq!(
+            Aes128::new().decrypt(cipher_text, recovered).unwrap(),
+            b"operators only"
+        );
+    }
+}
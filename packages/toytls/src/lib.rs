@@ -0,0 +1,31 @@
+//! Toy SSLv3 / TLS 1.0 style record layer, MAC-then-encrypt with AES-128 CBC
+//! and HMAC-SHA256, for demonstrating padding attacks. Not a real protocol.
+
+use thiserror::Error;
+
+pub mod channel;
+pub mod record;
+
+pub use channel::{pair, Endpoint};
+pub use record::{RecordLayer, Version};
+
+pub type TlsResult<T> = Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    // same alert for bad padding and bad MAC, the only difference left is timing
+    #[error("bad_record_mac")]
+    BadRecordMac,
+
+    #[error("decode_error, record is not a whole number of blocks")]
+    DecodeError,
+
+    #[error("Invalid padding length {0} for this record")]
+    InvalidPadding(usize),
+
+    #[error("Peer disconnected")]
+    Disconnected,
+
+    #[error(transparent)]
+    Cipher(#[from] anyhow::Error),
+}
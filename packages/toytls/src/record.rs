@@ -0,0 +1,235 @@
+use std::cell::RefCell;
+
+use aes::{
+    cipher::AesCipher, mac::ct_eq, modes::CbcMode, padding::NoPadding, secret::Secret, Modes,
+};
+use hash::HmacSha256;
+use prng::{OsRng, Rng};
+
+use crate::{Error, TlsResult};
+
+pub const BLOCK_SIZE: usize = 16;
+pub const MAC_LEN: usize = 32;
+
+// padding is handled by the record layer itself
+type RawCbc = CbcMode<AesCipher<128>, BLOCK_SIZE, NoPadding>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    // arbitrary padding bytes, only the length byte is checked
+    Ssl3,
+    // every padding byte equals the length byte, up to 255 bytes
+    Tls,
+}
+
+impl Version {
+    fn wire(&self) -> [u8; 2] {
+        match self {
+            Version::Ssl3 => [3, 0],
+            Version::Tls => [3, 1],
+        }
+    }
+}
+
+/// One direction of a connection.
+///
+/// A record is `IV || CBC(data || MAC || padding || padding_len)` with
+/// `MAC = HMAC(mac_key, version || len(data) || data)` and a fresh random IV.
+/// IVs and SSLv3 padding bytes are drawn from `R`, the OS by default.
+#[derive(Debug)]
+pub struct RecordLayer<R = OsRng> {
+    version: Version,
+    enc_key: Secret<Vec<u8>>,
+    mac_key: Secret<Vec<u8>>,
+    rng: RefCell<R>,
+}
+
+impl RecordLayer {
+    /// IVs and padding from the OS, tests should use [`Self::with_rng`] so
+    /// that a failing run can be replayed.
+    pub fn new<K: AsRef<[u8]>, M: AsRef<[u8]>>(version: Version, enc_key: K, mac_key: M) -> Self {
+        Self::with_rng(version, enc_key, mac_key, OsRng)
+    }
+}
+
+impl<R: Rng> RecordLayer<R> {
+    /// Records from a seeded `rng` can be replayed exactly.
+    pub fn with_rng<K: AsRef<[u8]>, M: AsRef<[u8]>>(
+        version: Version,
+        enc_key: K,
+        mac_key: M,
+        rng: R,
+    ) -> Self {
+        RecordLayer {
+            version,
+            enc_key: Secret::new(enc_key.as_ref().to_vec()),
+            mac_key: Secret::new(mac_key.as_ref().to_vec()),
+            rng: RefCell::new(rng),
+        }
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    // minimal padding
+    pub fn seal<T: AsRef<[u8]>>(&self, data: T) -> TlsResult<Vec<u8>> {
+        let data = data.as_ref();
+        let pad_len = BLOCK_SIZE - 1 - (data.len() + MAC_LEN) % BLOCK_SIZE;
+        self.seal_with_padding(data, pad_len)
+    }
+
+    /// Seal with `pad_len` padding bytes (plus the length byte), TLS allows
+    /// up to 255 to hide the length of the data, SSLv3 less than a block.
+    pub fn seal_with_padding<T: AsRef<[u8]>>(&self, data: T, pad_len: usize) -> TlsResult<Vec<u8>> {
+        let data = data.as_ref();
+        let max_pad = match self.version {
+            Version::Ssl3 => BLOCK_SIZE - 1,
+            Version::Tls => 255,
+        };
+        if pad_len > max_pad || !(data.len() + MAC_LEN + pad_len + 1).is_multiple_of(BLOCK_SIZE) {
+            return Err(Error::InvalidPadding(pad_len));
+        }
+
+        let rng = &mut *self.rng.borrow_mut();
+        let padding = match self.version {
+            Version::Ssl3 => rng.random_bytes(pad_len),
+            Version::Tls => vec![pad_len as u8; pad_len],
+        };
+        let plain_text = Secret::new([data, &self.mac(data), &padding, &[pad_len as u8]].concat());
+
+        let iv = rng.random_bytes(BLOCK_SIZE);
+        let cipher_text = RawCbc::with_iv(iv.clone()).encrypt(&*plain_text, &*self.enc_key)?;
+        Ok([iv, cipher_text].concat())
+    }
+
+    /// Decrypt, check the padding then the MAC.
+    ///
+    /// As recommended since TLS 1.1 a bad TLS padding is treated as empty
+    /// and the MAC is still computed, so both failures take almost the same
+    /// time. The MAC is over fewer bytes when the padding is long and valid,
+    /// which is what Lucky13 measures.
+    pub fn open<T: AsRef<[u8]>>(&self, record: T) -> TlsResult<Vec<u8>> {
+        let record = record.as_ref();
+        let min_len = BLOCK_SIZE + (MAC_LEN + 1).div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+        if record.len() < min_len || record.len() % BLOCK_SIZE != 0 {
+            return Err(Error::DecodeError);
+        }
+
+        let (iv, cipher_text) = record.split_at(BLOCK_SIZE);
+        let plain_text =
+            Secret::new(RawCbc::with_iv(iv.to_vec()).decrypt(cipher_text, &*self.enc_key)?);
+        let pad_len = *plain_text.last().unwrap() as usize;
+
+        let (pad_ok, strip) = match self.version {
+            Version::Ssl3 => (pad_len < BLOCK_SIZE, pad_len + 1),
+            Version::Tls => {
+                let fits = pad_len + 1 + MAC_LEN <= plain_text.len();
+                let ok = fits
+                    && plain_text[plain_text.len() - 1 - pad_len..]
+                        .iter()
+                        .all(|&b| b as usize == pad_len);
+                (ok, if ok { pad_len + 1 } else { 1 })
+            }
+        };
+        if self.version == Version::Ssl3 && !pad_ok {
+            return Err(Error::BadRecordMac);
+        }
+
+        let (data, tag) =
+            plain_text[..plain_text.len() - strip].split_at(plain_text.len() - strip - MAC_LEN);
+        let mac_ok = ct_eq(self.mac(data), tag);
+        if pad_ok && mac_ok {
+            Ok(data.to_vec())
+        } else {
+            Err(Error::BadRecordMac)
+        }
+    }
+
+    fn mac(&self, data: &[u8]) -> Vec<u8> {
+        let mut mac = HmacSha256::new(&*self.mac_key);
+        mac.update(self.version.wire());
+        mac.update((data.len() as u16).to_be_bytes());
+        mac.update(data);
+        mac.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prng::SeededRng;
+
+    const ENC_KEY: &[u8; 16] = b"YELLOW SUBMARINE";
+    const MAC_KEY: &[u8; 32] = b"MAC KEY MAC KEY MAC KEY MAC KEY!";
+
+    fn seeded(version: Version) -> RecordLayer<SeededRng> {
+        RecordLayer::with_rng(version, ENC_KEY, MAC_KEY, SeededRng::from_test_env())
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for version in [Version::Ssl3, Version::Tls] {
+            let layer = seeded(version);
+            for len in [0, 1, 15, 16, 47, 100] {
+                let data = vec![b'x'; len];
+                let record = layer.seal(&data).unwrap();
+                assert_eq!(record.len() % BLOCK_SIZE, 0);
+                assert_eq!(layer.open(&record).unwrap(), data);
+            }
+        }
+
+        let tls = seeded(Version::Tls);
+        let record = tls.seal_with_padding(b"hide my length", 161).unwrap();
+        assert_eq!(tls.open(record).unwrap(), b"hide my length");
+        assert!(tls.seal_with_padding(b"hide my length", 160).is_err());
+
+        let ssl3 = seeded(Version::Ssl3);
+        assert!(ssl3.seal_with_padding(b"hide my length", 161).is_err());
+    }
+
+    #[test]
+    fn test_seeded_replay() {
+        for version in [Version::Ssl3, Version::Tls] {
+            let a = RecordLayer::with_rng(version, ENC_KEY, MAC_KEY, SeededRng::new(40));
+            let b = RecordLayer::with_rng(version, ENC_KEY, MAC_KEY, SeededRng::new(40));
+            let record = a.seal_with_padding([0; 20], 11).unwrap();
+            assert_eq!(b.seal_with_padding([0; 20], 11).unwrap(), record);
+            assert_ne!(a.seal_with_padding([0; 20], 11).unwrap(), record);
+        }
+    }
+
+    #[test]
+    fn test_tampering_rejected() {
+        for version in [Version::Ssl3, Version::Tls] {
+            let layer = seeded(version);
+            let record = layer.seal("GET / HTTP/1.1").unwrap();
+            for i in 0..record.len() {
+                let mut tampered = record.clone();
+                tampered[i] ^= 0x80;
+                assert!(matches!(layer.open(&tampered), Err(Error::BadRecordMac)));
+            }
+            assert!(matches!(
+                layer.open(&record[..record.len() - 1]),
+                Err(Error::DecodeError)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ssl3_padding_not_checked() {
+        // only the length byte of SSLv3 padding is checked, TLS checks all
+        let ssl3 = seeded(Version::Ssl3);
+        let tls = seeded(Version::Tls);
+        for (layer, accepted) in [(ssl3, true), (tls, false)] {
+            let data = [1; 16];
+            let plain_text = [&data[..], &layer.mac(&data), &[0xee; 15], &[15]].concat();
+            let iv = vec![0; BLOCK_SIZE];
+            let cipher_text = RawCbc::with_iv(iv.clone())
+                .encrypt(plain_text, ENC_KEY)
+                .unwrap();
+            let record = [iv, cipher_text].concat();
+            assert_eq!(layer.open(&record).is_ok(), accepted);
+        }
+    }
+}